@@ -1,8 +1,18 @@
 //mod enum_b;
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use lattice_graph::{fixedvec2d::FixedVec2D, lattice_abstract::square::SquareAxis, SquareGraph};
-use petgraph::{algo, graph::*, visit::EdgeRef};
+use lattice_graph::{
+    algo::astar_grid,
+    fixedvec2d::FixedVec2D,
+    hex::axial_based::{HexGraphConst, OddR},
+    lattice_abstract::square::SquareAxis,
+    SquareGraph,
+};
+use petgraph::{
+    algo,
+    graph::*,
+    visit::{EdgeRef, IntoEdges, IntoNeighbors, NodeCount, NodeIndexable},
+};
 use rand::{prelude::StdRng, Rng, SeedableRng};
 use std::num::NonZeroUsize;
 
@@ -98,6 +108,39 @@ fn graph_search_inner(c: &mut Criterion, h: u32, v: u32, seed: u64, name: &'stat
             },
         )
     });
+    g.bench_function("lattice_abst_cached", |b| {
+        let mut r = StdRng::seed_from_u64(seed);
+        let g = lattice_graph::lattice_abstract::square::SquareGraphAbstract::<_, _>::new_with(
+            lattice_graph::lattice_abstract::square::SquareShape::new(h as usize, v as usize),
+            |_| (),
+            |o, d| o.0.horizontal() + o.0.vertical() + if d == SquareAxis::X { 0 } else { 1 },
+        )
+        .with_connectivity_cache();
+        b.iter_with_setup(
+            || (&g, (r.gen_range(0..h) as usize, r.gen_range(0..v) as usize)),
+            |(g, t)| {
+                black_box(
+                    algo::astar(g, (0, 0).into(), |x| x == t, |x| *x.weight(), |_| 0).is_some(),
+                );
+            },
+        )
+    });
+    g.bench_function("lattice_abst_astar_grid", |b| {
+        let mut r = StdRng::seed_from_u64(seed);
+        let g = lattice_graph::lattice_abstract::square::SquareGraphAbstract::<_, _>::new_with(
+            lattice_graph::lattice_abstract::square::SquareShape::new(h as usize, v as usize),
+            |_| (),
+            |o, d| o.0.horizontal() + o.0.vertical() + if d == SquareAxis::X { 0 } else { 1 },
+        );
+        b.iter_with_setup(
+            || (&g, (r.gen_range(0..h) as usize, r.gen_range(0..v) as usize)),
+            |(g, t)| {
+                black_box(
+                    astar_grid(g, (0, 0).into(), |x| x == t, |x| *x.weight(), |_| 0).is_some(),
+                );
+            },
+        )
+    });
 }
 
 fn graph_search_small(c: &mut Criterion) {
@@ -108,6 +151,55 @@ fn graph_search_large(c: &mut Criterion) {
     graph_search_inner(c, 2000, 2000, 12345, "astar_large")
 }
 
+fn hex_neighbors(c: &mut Criterion) {
+    type Hex = HexGraphConst<(), (), OddR, 64, 64>;
+    let graph = Hex::new_with_s(|_| (), |_, _| ());
+    let mut g = c.benchmark_group("hex_neighbors");
+    g.bench_function("move_coord", |b| {
+        b.iter(|| {
+            for i in 0..graph.node_count() {
+                for n in graph.neighbors(graph.from_index(i)) {
+                    black_box(n);
+                }
+            }
+        })
+    });
+    let table = graph.neighbor_table();
+    g.bench_function("table", |b| {
+        b.iter(|| {
+            for i in 0..graph.node_count() {
+                for n in table.neighbors_by_index(i) {
+                    black_box(n);
+                }
+            }
+        })
+    });
+}
+
+fn hex_adjacencies(c: &mut Criterion) {
+    type Hex = HexGraphConst<(), u32, OddR, 64, 64>;
+    let graph = Hex::new_with_s(|_| (), |c, _| (c.q() + c.r()) as u32);
+    let mut g = c.benchmark_group("hex_adjacencies");
+    g.bench_function("edges", |b| {
+        b.iter(|| {
+            for i in 0..graph.node_count() {
+                for e in graph.edges(graph.from_index(i)) {
+                    black_box((*e.direction(), e.target(), e.weight()));
+                }
+            }
+        })
+    });
+    g.bench_function("adjacencies", |b| {
+        b.iter(|| {
+            for i in 0..graph.node_count() {
+                for a in graph.adjacencies(graph.from_index(i)) {
+                    black_box(a);
+                }
+            }
+        })
+    });
+}
+
 fn array2d(c: &mut Criterion) {
     let mut g = c.benchmark_group("array2d");
     g.bench_function("jag", |b| {
@@ -152,7 +244,9 @@ criterion_group!(
     bench_graph,
     graph_build,
     graph_search_small,
-    graph_search_large
+    graph_search_large,
+    hex_neighbors,
+    hex_adjacencies
 );
 criterion_group!(array2ds, array2d);
 criterion_main!(bench_graph, array2ds);
@@ -0,0 +1,346 @@
+use std::collections::BinaryHeap;
+
+use fixedbitset::FixedBitSet;
+use petgraph::{
+    algo::{astar, Measure},
+    data::DataMap,
+    graph::IndexType,
+    visit::IntoEdges,
+};
+
+use super::dijkstra::MinScored;
+use crate::{
+    hex::axial_based::{HexAxial, HexAxialShape},
+    lattice_abstract::{
+        shapes::{Axis, AxisDirection, Shape},
+        EdgeReference, LatticeGraph,
+    },
+    square::{self, NodeIndex, SquareGraph},
+};
+
+/**
+A* search for [`LatticeGraph`] which uses arrays indexed by [`to_index`](`Shape::to_index`)
+instead of [`HashMap`](`std::collections::HashMap`) used in [`astar`](`petgraph::algo::astar`).
+The direction to come to each node is recorded instead of the node, and the path is reconstructed at the end.
+
+The arguments and the return value are same as [`astar`](`petgraph::algo::astar`).
+```
+# use lattice_graph::algo::astar_grid;
+# use lattice_graph::lattice_abstract::square::*;
+# use petgraph::visit::EdgeRef;
+let graph = SquareGraphAbstract::<(), u32>::new_with(SquareShape::new(3, 2), |_| (), |_, _| 1);
+let (cost, path) = astar_grid(&graph, (0, 0).into(), |c| c == (2, 1), |e| *e.weight(), |_| 0).unwrap();
+assert_eq!(cost, 3);
+assert_eq!(path.len(), 4);
+```
+*/
+pub fn astar_grid<N, E, S, C, A, D, IsGoal, F, H, K>(
+    graph: &LatticeGraph<N, E, S>,
+    start: C,
+    mut is_goal: IsGoal,
+    mut edge_cost: F,
+    mut estimate_cost: H,
+) -> Option<(K, Vec<C>)>
+where
+    S: Shape<Coordinate = C, Axis = A>,
+    C: Copy,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+    IsGoal: FnMut(C) -> bool,
+    F: FnMut(EdgeReference<'_, C, E, D, A>) -> K,
+    H: FnMut(C) -> K,
+    K: Measure + Copy,
+{
+    let s = graph.shape();
+    let start_index = s.to_index(start)?;
+    let n = s.node_count();
+    let mut costs: Vec<Option<K>> = vec![None; n];
+    let mut came_from: Vec<Option<D>> = vec![None; n];
+    let mut closed = FixedBitSet::with_capacity(n);
+    let mut heap = BinaryHeap::new();
+    costs[start_index] = Some(K::default());
+    heap.push(MinScored(estimate_cost(start), start_index));
+
+    while let Some(MinScored(_, i)) = heap.pop() {
+        if closed.put(i) {
+            continue;
+        }
+        let c = s.from_index(i);
+        let cost = unsafe {
+            costs
+                .get_unchecked(i)
+                .unwrap_or_else(|| crate::unreachable_debug_checked())
+        };
+        if is_goal(c) {
+            let mut path = vec![c];
+            let mut c = c;
+            let mut i = i;
+            while let Some(d) = came_from[i] {
+                let a = A::from_direction(d);
+                let back = if A::is_forward_direction(&d) {
+                    a.backward()
+                } else {
+                    a.foward()
+                };
+                // SAFETY : the node is moved from the previous node with `d`.
+                c = unsafe { s.move_coord_unchecked(c, back) };
+                i = unsafe {
+                    s.to_index(c)
+                        .unwrap_or_else(|| crate::unreachable_debug_checked())
+                };
+                path.push(c);
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+        for e in graph.edges(c) {
+            let t = e.target_id;
+            let ti = unsafe {
+                s.to_index(t)
+                    .unwrap_or_else(|| crate::unreachable_debug_checked())
+            };
+            if closed.contains(ti) {
+                continue;
+            }
+            let next = cost + edge_cost(e);
+            match costs[ti] {
+                Some(old) if old <= next => {}
+                _ => {
+                    costs[ti] = Some(next);
+                    came_from[ti] = Some(e.direction);
+                    heap.push(MinScored(next + estimate_cost(t), ti));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Converts the step count to the cost for the heuristic. It is clamped to [`u16::MAX`] to be converted to most types.
+fn steps<K: From<u16>>(d: usize) -> K {
+    K::from(d.min(u16::MAX as usize) as u16)
+}
+
+/**
+A* search from `start` to `goal` for [`SquareGraph`] with the manhattan distance ([`NodeIndex::distance`]) as the heuristic.
+The distance is taken across the border in the axis which loops, so that it doesn't overestimate.
+The heuristic assumes that every edge costs at least `K::from(1)`, otherwise the path found might not be the shortest.
+
+The return value is same as [`astar`](`petgraph::algo::astar`). Returns [`None`] if `start` or `goal` is not in the graph.
+```
+# use lattice_graph::{SquareGraph, algo::astar_square};
+# use petgraph::visit::EdgeRef;
+let graph = SquareGraph::<(), u32>::new_with(3, 2, |_, _| (), |_, _, _| 1);
+let (cost, path) = astar_square(&graph, (0, 0).into(), (2, 1).into(), |e| *e.weight()).unwrap();
+assert_eq!(cost, 3);
+assert_eq!(path.len(), 4);
+```
+*/
+pub fn astar_square<'a, N, E, Ix, S, F, K>(
+    graph: &'a SquareGraph<N, E, Ix, S>,
+    start: NodeIndex<Ix>,
+    goal: NodeIndex<Ix>,
+    edge_cost: F,
+) -> Option<(K, Vec<NodeIndex<Ix>>)>
+where
+    E: Copy,
+    Ix: IndexType,
+    S: square::Shape,
+    F: FnMut(square::EdgeReference<'a, E, Ix, S>) -> K,
+    K: Measure + Copy + From<u16>,
+{
+    graph.node_weight(start)?;
+    graph.node_weight(goal)?;
+    let (h, v) = (graph.horizontal_node_count(), graph.vertical_node_count());
+    let distance = move |a: usize, b: usize, len: usize, looping: bool| {
+        let d = a.abs_diff(b);
+        if looping {
+            d.min(len - d)
+        } else {
+            d
+        }
+    };
+    astar(
+        graph,
+        start,
+        |n| n == goal,
+        edge_cost,
+        |n| {
+            let dh = distance(
+                n.horizontal.index(),
+                goal.horizontal.index(),
+                h,
+                S::LOOP_HORIZONTAL,
+            );
+            let dv = distance(
+                n.vertical.index(),
+                goal.vertical.index(),
+                v,
+                S::LOOP_VERTICAL,
+            );
+            steps(dh + dv)
+        },
+    )
+}
+
+/**
+A* search from `start` to `goal` for the hex graph with [`HexAxial`] coordinates,
+with [`HexAxial::distance`] as the heuristic. It uses [`astar_grid`] inside.
+The heuristic assumes that every edge costs at least `K::from(1)`, otherwise the path found might not be the shortest.
+The shapes which loop are not supported, since the distance overestimates across the border.
+Use [`astar_grid`] with your own heuristic for them.
+
+The return value is same as [`astar`](`petgraph::algo::astar`). Returns [`None`] if `start` is not in the graph.
+```
+# use lattice_graph::{algo::astar_hex, hex::axial_based::*};
+# use petgraph::visit::EdgeRef;
+let graph = HexGraph::<(), u32>::new_with(HexAxialShape::new(5, 5), |_| (), |_, _| 1);
+let (cost, path) = astar_hex(&graph, HexAxial::new(0, 0), HexAxial::new(2, 2), |e| *e.weight()).unwrap();
+assert_eq!(cost, 4);
+assert_eq!(path.len(), 5);
+```
+*/
+pub fn astar_hex<N, E, B, H, V, A, D, F, K>(
+    graph: &LatticeGraph<N, E, HexAxialShape<B, (), H, V>>,
+    start: HexAxial,
+    goal: HexAxial,
+    edge_cost: F,
+) -> Option<(K, Vec<HexAxial>)>
+where
+    HexAxialShape<B, (), H, V>: Shape<Coordinate = HexAxial, Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+    F: FnMut(EdgeReference<'_, HexAxial, E, D, A>) -> K,
+    K: Measure + Copy + From<u16>,
+{
+    astar_grid(
+        graph,
+        start,
+        |c| c == goal,
+        edge_cost,
+        |c| steps(c.distance(&goal)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hex::axial_based::*,
+        lattice_abstract::{square::*, Offset},
+    };
+    use petgraph::{algo::astar, visit::EdgeRef};
+
+    #[test]
+    fn astar_grid_square() {
+        let sq = SquareGraphAbstract::new_with(
+            SquareShape::new(4, 3),
+            |SquareOffset(Offset {
+                 horizontal: x,
+                 vertical: y,
+             })| x + 2 * y,
+            |SquareOffset(Offset {
+                 horizontal: x,
+                 vertical: y,
+             }),
+             d| { (x + 2 * y) as i32 * if d == SquareAxis::X { 1 } else { 3 } },
+        );
+        for &(from, to) in &[((0, 0), (2, 1)), ((2, 1), (0, 0)), ((3, 2), (0, 1))] {
+            let h = |x: SquareOffset| {
+                (x.0.horizontal as i32 - to.0 as i32).abs()
+                    + (x.0.vertical as i32 - to.1 as i32).abs()
+            };
+            let expected = astar(&sq, from.into(), |x| x == to, |e| *e.weight(), h);
+            let actual = astar_grid(&sq, from.into(), |x| x == to, |e| *e.weight(), h);
+            assert_eq!(actual, expected);
+        }
+        let (d, p) =
+            astar_grid(&sq, (0, 0).into(), |x| x == (2, 1), |e| *e.weight(), |_| 0).unwrap();
+        assert_eq!(d, 5);
+        assert_eq!(p, [(0, 0), (0, 1), (1, 1), (2, 1)]);
+        assert_eq!(
+            astar_grid(&sq, (0, 0).into(), |_| false, |e| *e.weight(), |_| 0),
+            None
+        );
+        assert_eq!(
+            astar_grid(&sq, (4, 0).into(), |_| true, |e| *e.weight(), |_| 0),
+            None
+        );
+    }
+
+    #[test]
+    fn astar_grid_hex_loop() {
+        let graph = HexGraphConstLoopEW::<(), u32, OddR, 5, 5>::new_with_s(|_| (), |_, _| 1);
+        let from = HexAxial::new(0, 0);
+        let to = HexAxial::new(4, 0);
+        let (d, p) = astar_grid(&graph, from, |x| x == to, |e| *e.weight(), |_| 0).unwrap();
+        assert_eq!(d, 1);
+        assert_eq!(p, [from, to]);
+        let expected = astar(
+            &graph,
+            from,
+            |x| x == HexAxial::new(1, 3),
+            |e| *e.weight(),
+            |_| 0,
+        );
+        let actual = astar_grid(
+            &graph,
+            from,
+            |x| x == HexAxial::new(1, 3),
+            |e| *e.weight(),
+            |_| 0,
+        );
+        assert_eq!(actual.map(|x| x.0), expected.map(|x| x.0));
+    }
+
+    #[test]
+    fn astar_hex_heuristic() {
+        let graph = HexGraphConst::<(), u32, OddR, 5, 5>::new_with_s(
+            |_| (),
+            |c, a| 1 + (c.r() + c.q()).unsigned_abs() as u32 + a.to_index() as u32,
+        );
+        for &(from, to) in &[
+            (HexAxial::new(0, 0), HexAxial::new(2, 4)),
+            (HexAxial::new(2, 4), HexAxial::new(0, 0)),
+            (HexAxial::new(4, 0), HexAxial::new(-1, 3)),
+        ] {
+            let expected = astar(&graph, from, |x| x == to, |e| *e.weight(), |_| 0);
+            let actual = astar_hex(&graph, from, to, |e| *e.weight());
+            assert_eq!(actual.as_ref().map(|x| x.0), expected.map(|x| x.0));
+            let (_, path) = actual.unwrap();
+            assert_eq!((path[0], path[path.len() - 1]), (from, to));
+        }
+        let (d, p) = astar_hex(&graph, HexAxial::new(1, 1), HexAxial::new(1, 1), |_| 1u32).unwrap();
+        assert_eq!((d, p), (0, vec![HexAxial::new(1, 1)]));
+        assert_eq!(
+            astar_hex(&graph, HexAxial::new(0, 0), HexAxial::new(5, 0), |_| 1u32),
+            None
+        );
+    }
+
+    #[test]
+    fn astar_grid_cost_types() {
+        use ordered_float::OrderedFloat;
+        let graph = SquareGraphAbstract::<(), u8>::new_with(
+            SquareShape::new(4, 4),
+            |_| (),
+            |c, a| (c.0.horizontal + c.0.vertical) as u8 + if a == SquareAxis::X { 1 } else { 2 },
+        );
+        let (from, to) = (SquareOffset::from((0, 0)), SquareOffset::from((3, 3)));
+        let h = |c: SquareOffset| (6 - c.0.horizontal - c.0.vertical) as u32;
+        let (du, pu) = astar_grid(&graph, from, |x| x == to, |e| *e.weight() as u32, h).unwrap();
+        let (df, pf) = astar_grid(
+            &graph,
+            from,
+            |x| x == to,
+            |e| OrderedFloat(*e.weight() as f64 / 2.),
+            |c| OrderedFloat(h(c) as f64 / 2.),
+        )
+        .unwrap();
+        let expected = astar(&graph, from, |x| x == to, |e| *e.weight() as u32, h).unwrap();
+        assert_eq!(du, expected.0);
+        assert_eq!(df, OrderedFloat(du as f64 / 2.));
+        assert_eq!(pu.len(), 7);
+        assert_eq!(pf.len(), 7);
+    }
+}
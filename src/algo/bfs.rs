@@ -0,0 +1,236 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+use petgraph::visit::{IntoNeighbors, NodeIndexable, VisitMap, Visitable};
+
+/**
+Returns the hop counts from `start` to the reachable nodes by breadth first search.
+If `max_depth` is `Some`, the nodes farther than it are not searched.
+
+The visited nodes are tracked with the [`VisitMap`] of the graph,
+which is a bit set for the graphs in this crate instead of hashing.
+```
+# use lattice_graph::algo::bfs_distances;
+# use lattice_graph::lattice_abstract::square::*;
+let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(3, 3));
+let dist = bfs_distances(&graph, (0, 0).into(), Some(1));
+assert_eq!(dist.len(), 3);
+assert_eq!(dist[&SquareOffset::from((0, 1))], 1);
+```
+*/
+pub fn bfs_distances<G>(
+    graph: G,
+    start: G::NodeId,
+    max_depth: Option<u32>,
+) -> HashMap<G::NodeId, u32>
+where
+    G: IntoNeighbors + Visitable,
+    G::NodeId: Eq + Hash,
+{
+    let mut distances = HashMap::new();
+    let mut visited = graph.visit_map();
+    let mut queue = VecDeque::new();
+    visited.visit(start);
+    distances.insert(start, 0);
+    queue.push_back((start, 0));
+    while let Some((n, d)) = queue.pop_front() {
+        if max_depth == Some(d) {
+            continue;
+        }
+        for t in graph.neighbors(n) {
+            if visited.visit(t) {
+                distances.insert(t, d + 1);
+                queue.push_back((t, d + 1));
+            }
+        }
+    }
+    distances
+}
+
+/// Reusable buffers of breadth first search, which returns the same distances as [`bfs_distances`]
+/// without allocating for each search.
+///
+/// The visit map is created by the graph in the first run and reset by [`reset_map`](`Visitable::reset_map`) in the later runs.
+/// The graphs in this crate resize the map to their shape in `reset_map`, so it can be used for graphs of different shapes.
+/// ```
+/// # use lattice_graph::algo::GridBfs;
+/// # use lattice_graph::lattice_abstract::square::*;
+/// # use petgraph::visit::NodeIndexable;
+/// let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(3, 3));
+/// let mut bfs = GridBfs::new();
+/// let dist = bfs.run(&graph, (0, 0).into(), Some(1));
+/// assert_eq!(dist[graph.to_index((0, 1).into())], Some(1));
+/// assert_eq!(dist[graph.to_index((1, 1).into())], None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct GridBfs<N, M> {
+    queue: VecDeque<(N, u32)>,
+    visited: Option<M>,
+    distances: Vec<Option<u32>>,
+}
+
+impl<N, M> GridBfs<N, M> {
+    /// Creates an empty buffer.
+    pub fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            visited: None,
+            distances: Vec::new(),
+        }
+    }
+
+    /// Creates a buffer for graphs with `node_bound` nodes.
+    pub fn with_capacity(node_bound: usize) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            visited: None,
+            distances: Vec::with_capacity(node_bound),
+        }
+    }
+
+    /// Returns the hop counts of the last run indexed by [`to_index`](`NodeIndexable::to_index`),
+    /// which is [`None`] if the node is not reached.
+    pub fn distances(&self) -> &[Option<u32>] {
+        &self.distances
+    }
+
+    /// Search from `start` like [`bfs_distances`], and returns the hop counts like [`distances`](`Self::distances`).
+    /// The result of the previous run is cleared.
+    pub fn run<G>(&mut self, graph: G, start: N, max_depth: Option<u32>) -> &[Option<u32>]
+    where
+        G: IntoNeighbors<NodeId = N> + Visitable<Map = M> + NodeIndexable,
+        N: Copy,
+        M: VisitMap<N>,
+    {
+        let n = graph.node_bound();
+        let GridBfs {
+            queue,
+            visited,
+            distances,
+        } = self;
+        let visited = match visited {
+            Some(m) => {
+                graph.reset_map(m);
+                m
+            }
+            None => visited.get_or_insert_with(|| graph.visit_map()),
+        };
+        distances.clear();
+        distances.resize(n, None);
+        queue.clear();
+
+        visited.visit(start);
+        distances[graph.to_index(start)] = Some(0);
+        queue.push_back((start, 0));
+        while let Some((c, d)) = queue.pop_front() {
+            if max_depth == Some(d) {
+                continue;
+            }
+            for t in graph.neighbors(c) {
+                if visited.visit(t) {
+                    distances[graph.to_index(t)] = Some(d + 1);
+                    queue.push_back((t, d + 1));
+                }
+            }
+        }
+        &self.distances
+    }
+}
+
+impl<N, M> Default for GridBfs<N, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lattice_abstract::square::*, SquareGraph};
+
+    #[test]
+    fn bfs_distances_corner() {
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(5, 5));
+        let dist = bfs_distances(&graph, (0, 0).into(), None);
+        assert_eq!(dist.len(), 25);
+        for (c, d) in dist {
+            assert_eq!(d as usize, c.0.horizontal + c.0.vertical);
+        }
+    }
+
+    #[test]
+    fn bfs_distances_center_capped() {
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(5, 5));
+        let dist = bfs_distances(&graph, (2, 2).into(), Some(2));
+        assert_eq!(dist.len(), 1 + 4 + 8);
+        assert_eq!(dist[&SquareOffset::from((2, 2))], 0);
+        assert_eq!(dist[&SquareOffset::from((3, 2))], 1);
+        assert_eq!(dist[&SquareOffset::from((3, 3))], 2);
+        assert!(!dist.contains_key(&SquareOffset::from((0, 0))));
+        assert_eq!(bfs_distances(&graph, (2, 2).into(), Some(0)).len(), 1);
+
+        let graph = SquareGraph::<(), (), u32>::new(5, 5);
+        let dist = bfs_distances(&graph, (2, 2).into(), Some(2));
+        assert_eq!(dist.len(), 1 + 4 + 8);
+        assert_eq!(dist[&(4, 2).into()], 2);
+    }
+    #[test]
+    fn grid_bfs_reuse() {
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(5, 5));
+        let mut bfs = GridBfs::new();
+        let dist = bfs.run(&graph, (0, 0).into(), None).to_vec();
+        assert!(dist.iter().all(|d| d.is_some()));
+        let expected = bfs_distances(&graph, (0, 0).into(), None);
+        for (c, d) in expected.iter() {
+            assert_eq!(dist[graph.to_index(*c)], Some(*d));
+        }
+
+        // the nodes reached in the first run are not left in the second run.
+        let dist = bfs.run(&graph, (4, 4).into(), Some(1));
+        assert_eq!(dist.iter().filter(|d| d.is_some()).count(), 3);
+        assert_eq!(dist[graph.to_index((4, 4).into())], Some(0));
+        assert_eq!(dist[graph.to_index((3, 4).into())], Some(1));
+        assert_eq!(dist[graph.to_index((0, 0).into())], None);
+        let expected = bfs_distances(&graph, (2, 2).into(), Some(2));
+        let dist = bfs.run(&graph, (2, 2).into(), Some(2));
+        assert_eq!(dist.iter().filter(|d| d.is_some()).count(), expected.len());
+        for (c, d) in expected.iter() {
+            assert_eq!(dist[graph.to_index(*c)], Some(*d));
+        }
+        assert_eq!(bfs.distances().len(), 25);
+
+        // a graph of another size resizes the visit map.
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(2, 3));
+        let dist = bfs.run(&graph, (0, 0).into(), None);
+        assert_eq!(dist.len(), 6);
+        assert_eq!(dist[graph.to_index((1, 2).into())], Some(3));
+    }
+
+    #[test]
+    fn grid_bfs_swapped_dimensions() {
+        let wide = SquareGraphAbstract::<(), ()>::new(SquareShape::new(6, 4));
+        let tall = SquareGraphAbstract::<(), ()>::new(SquareShape::new(4, 6));
+        let mut bfs = GridBfs::new();
+        assert!(bfs
+            .run(&wide, (0, 0).into(), None)
+            .iter()
+            .all(|d| d.is_some()));
+        let dist = bfs.run(&tall, (0, 0).into(), None);
+        assert!(dist.iter().all(|d| d.is_some()));
+        assert_eq!(dist[tall.to_index((3, 5).into())], Some(8));
+        assert_eq!(dist[tall.to_index((0, 5).into())], Some(5));
+
+        let wide = SquareGraph::<(), (), u32>::new(6, 4);
+        let tall = SquareGraph::<(), (), u32>::new(4, 6);
+        let mut bfs = GridBfs::new();
+        assert!(bfs
+            .run(&wide, (0, 0).into(), None)
+            .iter()
+            .all(|d| d.is_some()));
+        let dist = bfs.run(&tall, (0, 0).into(), None);
+        assert!(dist.iter().all(|d| d.is_some()));
+        assert_eq!(dist[tall.to_index((3, 5).into())], Some(8));
+    }
+}
@@ -0,0 +1,134 @@
+use fixedbitset::FixedBitSet;
+
+use crate::lattice_abstract::{shapes::*, LatticeGraph};
+use petgraph::data::DataMap;
+
+/// (index, coordinate, next direction, edge from parent)
+type Frame<C, A> = (usize, C, usize, Option<(C, A)>);
+
+/**
+Returns the edges whose removal disconnects the passable nodes (bridges), using the DFS low-link.
+Only the edges between passable nodes are considered.
+The edges are returned as the ids which can be used in [`edge_weight`](`petgraph::data::DataMap::edge_weight`).
+
+This is for undirected graphs. For directed graphs, edges to both directions are distinct,
+so no edge is reported.
+```
+# use lattice_graph::algo::bridges;
+# use lattice_graph::lattice_abstract::square::*;
+// two 2x2 rooms are connected only through (2, 0)
+let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(5, 2));
+let mut b = bridges(&graph, |c, _| c != SquareOffset::from((2, 1)));
+b.sort();
+assert_eq!(b, vec![((1, 0).into(), SquareAxis::X), ((2, 0).into(), SquareAxis::X)]);
+```
+*/
+pub fn bridges<N, E, S, F>(
+    graph: &LatticeGraph<N, E, S>,
+    mut is_passable: F,
+) -> Vec<(S::Coordinate, S::Axis)>
+where
+    S: Shape,
+    S::Axis: PartialEq,
+    F: FnMut(S::Coordinate, &N) -> bool,
+{
+    let s = graph.shape();
+    let n = s.node_count();
+    let mut passable = FixedBitSet::with_capacity(n);
+    for i in 0..n {
+        let c = s.from_index(i);
+        if let Some(w) = graph.node_weight(c) {
+            passable.set(i, is_passable(c, w));
+        }
+    }
+
+    // 0 means not visited.
+    let mut disc = vec![0; n];
+    let mut low = vec![0; n];
+    let mut timer = 0;
+    let mut bridges = Vec::new();
+    let mut stack: Vec<Frame<S::Coordinate, S::Axis>> = Vec::new();
+    for root in 0..n {
+        if !passable[root] || disc[root] != 0 {
+            continue;
+        }
+        timer += 1;
+        disc[root] = timer;
+        low[root] = timer;
+        stack.push((root, s.from_index(root), 0, None));
+        while let Some(top) = stack.last_mut() {
+            let (vi, v, state, _) = *top;
+            if state < S::Axis::UNDIRECTED_COUNT {
+                top.2 += 1;
+                let d = unsafe { <S::Axis as Axis>::Direction::dir_from_index_unchecked(state) };
+                let t = match s.move_coord(v, d.clone()) {
+                    Ok(t) => t,
+                    Err(_) => continue,
+                };
+                let ti = match s.to_index(t) {
+                    Some(ti) if passable[ti] => ti,
+                    _ => continue,
+                };
+                let id = if S::Axis::is_forward_direction(&d) {
+                    (v, S::Axis::from_direction(d))
+                } else {
+                    (t, S::Axis::from_direction(d))
+                };
+                if top.3.as_ref() == Some(&id) {
+                    continue;
+                }
+                if disc[ti] == 0 {
+                    timer += 1;
+                    disc[ti] = timer;
+                    low[ti] = timer;
+                    stack.push((ti, t, 0, Some(id)));
+                } else {
+                    low[vi] = low[vi].min(disc[ti]);
+                }
+            } else {
+                let (_, _, _, parent_edge) = stack.pop().unwrap();
+                if let Some(&(pi, _, _, _)) = stack.last() {
+                    low[pi] = low[pi].min(low[vi]);
+                    if low[vi] > disc[pi] {
+                        bridges.extend(parent_edge);
+                    }
+                }
+            }
+        }
+    }
+    bridges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice_abstract::square::*;
+
+    #[test]
+    fn bridges_corridor() {
+        // two 3x3 rooms joined by the corridor (3, 1)
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(7, 3));
+        let mut b = bridges(&graph, |c, _| c.0.horizontal != 3 || c.0.vertical == 1);
+        b.sort();
+        assert_eq!(
+            b,
+            vec![
+                ((2, 1).into(), SquareAxis::X),
+                ((3, 1).into(), SquareAxis::X)
+            ]
+        );
+        assert!(bridges(&graph, |_, _| true).is_empty());
+        assert!(bridges(&graph, |_, _| false).is_empty());
+    }
+
+    #[test]
+    fn bridges_tree() {
+        // every edge of a line is a bridge
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(5, 1));
+        assert_eq!(bridges(&graph, |_, _| true).len(), 4);
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(4, 4));
+        // comb: column 0 and row 0, 2 are passable, which is a tree with 10 nodes
+        let b = bridges(&graph, |c, _| c.0.horizontal == 0 || c.0.vertical % 2 == 0);
+        assert_eq!(b.len(), 9);
+    }
+}
@@ -0,0 +1,99 @@
+use std::{collections::HashMap, hash::Hash};
+
+use petgraph::{
+    unionfind::UnionFind,
+    visit::{EdgeRef, IntoEdgeReferences, IntoNodeIdentifiers},
+};
+
+/**
+Labels the connected components of the graph, crossing only the edges which are `passable`.
+The labels are numbered from 0 in the order of [`node_identifiers`](`IntoNodeIdentifiers::node_identifiers`),
+so the node without passable edges has its own label.
+The direction of the edges is ignored, so it returns the weakly connected components for directed graphs.
+```
+# use lattice_graph::algo::components_with;
+# use lattice_graph::lattice_abstract::square::*;
+# use petgraph::visit::EdgeRef;
+let graph = SquareGraphAbstract::<(), bool>::new_with(SquareShape::new(3, 1), |_| (), |c, _| c.0.horizontal() != 1);
+let labels = components_with(&graph, |e| *e.weight());
+assert_eq!(labels[&(0, 0).into()], 0);
+assert_eq!(labels[&(1, 0).into()], 0);
+assert_eq!(labels[&(2, 0).into()], 1);
+```
+*/
+pub fn components_with<G, P>(graph: G, mut passable: P) -> HashMap<G::NodeId, usize>
+where
+    G: IntoEdgeReferences + IntoNodeIdentifiers,
+    G::NodeId: Eq + Hash,
+    P: FnMut(&G::EdgeRef) -> bool,
+{
+    let index: HashMap<G::NodeId, usize> = graph
+        .node_identifiers()
+        .enumerate()
+        .map(|(i, n)| (n, i))
+        .collect();
+    let mut uf = UnionFind::new(index.len());
+    for e in graph.edge_references() {
+        if !passable(&e) {
+            continue;
+        }
+        if let (Some(s), Some(t)) = (index.get(&e.source()), index.get(&e.target())) {
+            uf.union(*s, *t);
+        }
+    }
+    // relabel the representatives in the order of the nodes.
+    let mut labels = HashMap::new();
+    let mut components = Vec::with_capacity(index.len());
+    for n in graph.node_identifiers() {
+        let root = uf.find_mut(index[&n]);
+        let next = labels.len();
+        let label = *labels.entry(root).or_insert(next);
+        components.push((n, label));
+    }
+    components.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hex::axial_based::{HexAxial, HexGraphConst, OddR},
+        square::{Axis, NodeIndex},
+        SquareGraph,
+    };
+
+    #[test]
+    fn components_with_wall() {
+        // the horizontal edges between the 2nd and 3rd columns are impassable.
+        let graph = SquareGraph::<(), bool, u32>::new_with(
+            4,
+            4,
+            |_, _| (),
+            |h, _, a| !(a == Axis::Horizontal && h == 1),
+        );
+        let labels = components_with(&graph, |e| *e.weight());
+        assert_eq!(labels.len(), 16);
+        for (n, l) in labels.iter() {
+            assert_eq!(*l, if n.horizontal < 2 { 0 } else { 1 });
+        }
+        let labels = components_with(&graph, |_| true);
+        assert!(labels.values().all(|l| *l == 0));
+        let labels = components_with(&graph, |_| false);
+        assert_eq!(labels[&NodeIndex::new(0, 0)], 0);
+        assert_eq!(labels[&NodeIndex::new(3, 3)], 15);
+    }
+
+    #[test]
+    fn components_with_hex() {
+        type Hex = HexGraphConst<(), u32, OddR, 5, 5>;
+        // the nodes with q == 2 are surrounded by impassable edges.
+        let graph = Hex::new_with_s(|_| (), |_, _| 1);
+        let labels = components_with(&graph, |e| {
+            *e.weight() == 1 && (e.source().q() == 2) == (e.target().q() == 2)
+        });
+        assert_eq!(labels.values().max(), Some(&2));
+        assert_eq!(labels[&HexAxial::new(0, 0)], 0);
+        assert_eq!(labels[&HexAxial::new(0, 2)], 1);
+        assert_eq!(labels[&HexAxial::new(0, 4)], 2);
+    }
+}
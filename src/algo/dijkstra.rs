@@ -0,0 +1,175 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use fixedbitset::FixedBitSet;
+use petgraph::{
+    algo::Measure,
+    visit::{EdgeRef, IntoEdges, NodeIndexable},
+};
+
+/// Reusable buffers for [`dijkstra_reuse`].
+///
+/// The buffers are resized to the [`node_bound`](`NodeIndexable::node_bound`) of the graph in each run,
+/// so a scratch can be used for graphs of different sizes,
+/// but it does not reallocate if it is used for graphs of the same (or smaller) size.
+#[derive(Debug, Clone)]
+pub struct PathScratch<C> {
+    costs: Vec<Option<C>>,
+    visited: FixedBitSet,
+    heap: BinaryHeap<MinScored<C>>,
+}
+
+impl<C> PathScratch<C> {
+    /// Creates an empty scratch.
+    pub fn new() -> Self {
+        Self {
+            costs: Vec::new(),
+            visited: FixedBitSet::with_capacity(0),
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    /// Creates a scratch for graphs with `node_bound` nodes.
+    pub fn with_capacity(node_bound: usize) -> Self {
+        Self {
+            costs: Vec::with_capacity(node_bound),
+            visited: FixedBitSet::with_capacity(node_bound),
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    /// Returns the costs of the last run. See [`dijkstra_reuse`].
+    pub fn costs(&self) -> &[Option<C>] {
+        &self.costs
+    }
+}
+
+impl<C> Default for PathScratch<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+Dijkstra which reuses the buffers in `scratch` instead of allocating for each search.
+Returns the costs from `source` indexed by [`to_index`](`NodeIndexable::to_index`),
+which is [`None`] if the node is not reachable.
+The result is valid until the next run with the same `scratch`.
+```
+# use lattice_graph::algo::*;
+# use lattice_graph::lattice_abstract::square::*;
+# use petgraph::visit::{EdgeRef, NodeIndexable};
+let graph = SquareGraphAbstract::<(), u32>::new_with(SquareShape::new(3, 2), |_| (), |_, _| 1);
+let mut scratch = PathScratch::new();
+let costs = dijkstra_reuse(&mut scratch, &graph, (0, 0).into(), |e| *e.weight());
+assert_eq!(costs[graph.to_index((2, 1).into())], Some(3));
+```
+*/
+pub fn dijkstra_reuse<G, F, C>(
+    scratch: &mut PathScratch<C>,
+    graph: G,
+    source: G::NodeId,
+    mut edge_cost: F,
+) -> &[Option<C>]
+where
+    G: IntoEdges + NodeIndexable,
+    F: FnMut(G::EdgeRef) -> C,
+    C: Measure + Copy,
+{
+    let n = graph.node_bound();
+    let PathScratch {
+        costs,
+        visited,
+        heap,
+    } = scratch;
+    costs.clear();
+    costs.resize(n, None);
+    visited.clear();
+    visited.grow(n);
+    heap.clear();
+
+    let s = graph.to_index(source);
+    costs[s] = Some(C::default());
+    heap.push(MinScored(C::default(), s));
+    while let Some(MinScored(cost, i)) = heap.pop() {
+        if visited.put(i) {
+            continue;
+        }
+        for edge in graph.edges(graph.from_index(i)) {
+            let t = graph.to_index(edge.target());
+            if visited.contains(t) {
+                continue;
+            }
+            let next = cost + edge_cost(edge);
+            match costs[t] {
+                Some(c) if c <= next => {}
+                _ => {
+                    costs[t] = Some(next);
+                    heap.push(MinScored(next, t));
+                }
+            }
+        }
+    }
+    &scratch.costs
+}
+
+/// Reversed order by the cost to use [`BinaryHeap`] as min heap.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct MinScored<C>(pub(super) C, pub(super) usize);
+
+impl<C: PartialOrd> PartialEq for MinScored<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<C: PartialOrd> Eq for MinScored<C> {}
+
+impl<C: PartialOrd> PartialOrd for MinScored<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<C: PartialOrd> Ord for MinScored<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.partial_cmp(&self.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice_abstract::square::*;
+    use petgraph::algo::dijkstra;
+
+    fn assert_same<G>(costs: &[Option<u32>], graph: G, source: G::NodeId)
+    where
+        G: IntoEdges<EdgeWeight = u32> + NodeIndexable + petgraph::visit::Visitable,
+        G::NodeId: Eq + std::hash::Hash,
+    {
+        let fresh = dijkstra(graph, source, None, |e| *e.weight());
+        assert_eq!(costs.len(), graph.node_bound());
+        for (i, c) in costs.iter().enumerate() {
+            assert_eq!(c.as_ref(), fresh.get(&graph.from_index(i)));
+        }
+    }
+
+    #[test]
+    fn dijkstra_reuse_same_as_fresh() {
+        let graph = SquareGraphAbstract::<(), u32>::new_with(
+            SquareShape::new(5, 4),
+            |_| (),
+            |SquareOffset(o), a| (o.horizontal * 3 + o.vertical * 7 + a as usize) as u32 % 5 + 1,
+        );
+        let small =
+            SquareGraphAbstract::<(), u32>::new_with(SquareShape::new(2, 2), |_| (), |_, _| 2);
+        let mut scratch = PathScratch::new();
+        for &s in &[(0, 0), (3, 2), (0, 0)] {
+            let costs = dijkstra_reuse(&mut scratch, &graph, s.into(), |e| *e.weight());
+            assert_same(costs, &graph, s.into());
+        }
+        let costs = dijkstra_reuse(&mut scratch, &small, (1, 1).into(), |e| *e.weight());
+        assert_same(costs, &small, (1, 1).into());
+        assert_eq!(scratch.costs()[0], Some(4));
+    }
+}
@@ -0,0 +1,75 @@
+use std::hash::Hash;
+
+use petgraph::{
+    algo::{dijkstra, Measure},
+    visit::{IntoEdges, IntoNodeIdentifiers, Visitable},
+};
+
+/**
+Returns the nodes which have the same distance from `a` and `b` (the bisector of graph voronoi).
+The distances are calculated by dijkstra with `edge_cost`, and unreachable nodes from both are not included.
+```
+# use lattice_graph::algo::equidistant;
+# use lattice_graph::lattice_abstract::square::*;
+# use petgraph::visit::EdgeRef;
+let graph = SquareGraphAbstract::<(), u32>::new_with(SquareShape::new(5, 1), |_| (), |_, _| 1);
+let mid = equidistant(&graph, (0, 0).into(), (4, 0).into(), |e| *e.weight());
+assert_eq!(mid, vec![SquareOffset::from((2, 0))]);
+```
+*/
+pub fn equidistant<G, F, K>(
+    graph: G,
+    a: G::NodeId,
+    b: G::NodeId,
+    mut edge_cost: F,
+) -> Vec<G::NodeId>
+where
+    G: IntoEdges + IntoNodeIdentifiers + Visitable,
+    G::NodeId: Eq + Hash,
+    F: FnMut(G::EdgeRef) -> K,
+    K: Measure + Copy,
+{
+    let da = dijkstra(graph, a, None, &mut edge_cost);
+    let db = dijkstra(graph, b, None, &mut edge_cost);
+    graph
+        .node_identifiers()
+        .filter(|n| match (da.get(n), db.get(n)) {
+            (Some(x), Some(y)) => x == y,
+            _ => false,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice_abstract::square::*;
+    use petgraph::visit::EdgeRef;
+
+    #[test]
+    fn equidistant_line() {
+        let graph =
+            SquareGraphAbstract::<(), u32>::new_with(SquareShape::new(7, 1), |_| (), |_, _| 1);
+        let mid = equidistant(&graph, (0, 0).into(), (6, 0).into(), |e| *e.weight());
+        assert_eq!(mid, vec![SquareOffset::from((3, 0))]);
+        let mid = equidistant(&graph, (1, 0).into(), (4, 0).into(), |e| *e.weight());
+        assert!(mid.is_empty());
+        let mid = equidistant(&graph, (2, 0).into(), (2, 0).into(), |e| *e.weight());
+        assert_eq!(mid.len(), 7);
+    }
+
+    #[test]
+    fn equidistant_grid() {
+        let graph =
+            SquareGraphAbstract::<(), u32>::new_with(SquareShape::new(3, 3), |_| (), |_, _| 1);
+        let mid = equidistant(&graph, (0, 0).into(), (2, 2).into(), |e| *e.weight());
+        assert_eq!(
+            mid,
+            vec![
+                SquareOffset::from((0, 2)),
+                SquareOffset::from((1, 1)),
+                SquareOffset::from((2, 0))
+            ]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::VecDeque;
+
+use petgraph::visit::IntoEdges;
+
+use crate::lattice_abstract::{
+    shapes::{Axis, AxisDirection, Shape},
+    LatticeGraph,
+};
+
+/**
+Computes the maximum flow from `source` to `sink` with the Edmonds–Karp algorithm.
+`capacity` is called with the id of the edge, the same one used in [`edge_weight`](`petgraph::data::DataMap::edge_weight`).
+Each edge can be used in both directions and the capacity is shared by them.
+
+Returns 0 if `source` and `sink` are same or either of them is not in the graph.
+The total is `u64`, since it can exceed `u32::MAX` when several edges from `source` have large capacities.
+
+# Panics
+Panics if the axis of the graph is directed.
+```
+# use lattice_graph::algo::grid_max_flow;
+# use lattice_graph::lattice_abstract::square::*;
+let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(3, 2));
+assert_eq!(grid_max_flow(&graph, (0, 0).into(), (2, 1).into(), |_| 1), 2);
+```
+*/
+pub fn grid_max_flow<N, E, S, C, A, D, F>(
+    graph: &LatticeGraph<N, E, S>,
+    source: C,
+    sink: C,
+    capacity: F,
+) -> u64
+where
+    S: Shape<Coordinate = C, Axis = A>,
+    C: Copy,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+    F: Fn((C, A)) -> u32,
+{
+    assert!(!A::DIRECTED, "directed graph is not supported");
+    let s = graph.shape();
+    let (si, ti) = match (s.to_index(source), s.to_index(sink)) {
+        (Some(si), Some(ti)) if si != ti => (si, ti),
+        _ => return 0,
+    };
+    let n = s.node_count();
+    // flow of each edge in its forward direction, indexed by `node index * A::COUNT + axis index`.
+    let mut flow = vec![0i64; n * A::COUNT];
+    // the index of `flow`, whether `d` is forward, and the residual capacity of moving from `c` to `t` with `d`.
+    let residual = |flow: &[i64], c: C, t: C, d: D| {
+        let a = A::from_direction(d);
+        let forward = A::is_forward_direction(&d);
+        let id = if forward { c } else { t };
+        let i = unsafe {
+            s.to_index(id)
+                .unwrap_or_else(|| crate::unreachable_debug_checked())
+        } * A::COUNT
+            + a.to_index();
+        let cap = capacity((id, a)) as i64;
+        if forward {
+            (i, forward, cap - flow[i])
+        } else {
+            (i, forward, cap + flow[i])
+        }
+    };
+    let mut total = 0u64;
+    let mut came_from: Vec<Option<D>> = vec![None; n];
+    let mut queue = VecDeque::new();
+    loop {
+        came_from.iter_mut().for_each(|x| *x = None);
+        queue.clear();
+        queue.push_back(source);
+        let mut found = false;
+        'bfs: while let Some(c) = queue.pop_front() {
+            for e in graph.edges(c) {
+                let t = e.target_id;
+                let tj = unsafe {
+                    s.to_index(t)
+                        .unwrap_or_else(|| crate::unreachable_debug_checked())
+                };
+                if tj == si || came_from[tj].is_some() {
+                    continue;
+                }
+                if residual(&flow, c, t, e.direction).2 > 0 {
+                    came_from[tj] = Some(e.direction);
+                    if tj == ti {
+                        found = true;
+                        break 'bfs;
+                    }
+                    queue.push_back(t);
+                }
+            }
+        }
+        if !found {
+            return total;
+        }
+        // collect the edges of the path from the sink.
+        let mut path = Vec::new();
+        let mut t = sink;
+        let mut tj = ti;
+        while let Some(d) = came_from[tj] {
+            let a = A::from_direction(d);
+            let back = if A::is_forward_direction(&d) {
+                a.backward()
+            } else {
+                a.foward()
+            };
+            // SAFETY : the node is moved from the previous node with `d`.
+            let c = unsafe { s.move_coord_unchecked(t, back) };
+            path.push(residual(&flow, c, t, d));
+            t = c;
+            tj = unsafe {
+                s.to_index(c)
+                    .unwrap_or_else(|| crate::unreachable_debug_checked())
+            };
+        }
+        let f = path.iter().map(|p| p.2).min().unwrap_or(0);
+        for (i, forward, _) in path {
+            if forward {
+                flow[i] += f;
+            } else {
+                flow[i] -= f;
+            }
+        }
+        total += f as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice_abstract::square::*;
+
+    #[test]
+    fn grid_max_flow_bottleneck() {
+        // two 2x3 rooms connected with the single edge between (1, 1) and (2, 1).
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(4, 3));
+        let bottleneck = (SquareOffset::from((1, 1)), SquareAxis::X);
+        let capacity = |(c, a): (SquareOffset, SquareAxis)| {
+            if (c, a) == bottleneck {
+                7
+            } else if a == SquareAxis::X && c.0.horizontal == 1 {
+                0
+            } else {
+                10
+            }
+        };
+        assert_eq!(
+            grid_max_flow(&graph, (0, 0).into(), (3, 2).into(), capacity),
+            7
+        );
+        assert_eq!(
+            grid_max_flow(&graph, (3, 2).into(), (0, 0).into(), capacity),
+            7
+        );
+        // limited by the 3 edges between the 2nd and 3rd columns.
+        assert_eq!(
+            grid_max_flow(&graph, (1, 1).into(), (2, 1).into(), |_| 1),
+            3
+        );
+        assert_eq!(
+            grid_max_flow(&graph, (1, 1).into(), (1, 1).into(), |_| 1),
+            0
+        );
+        assert_eq!(
+            grid_max_flow(&graph, (1, 1).into(), (4, 1).into(), |_| 1),
+            0
+        );
+    }
+
+    #[test]
+    fn grid_max_flow_large_capacity() {
+        // the total exceeds the capacity u32::MAX of each edge.
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(3, 3));
+        assert_eq!(
+            grid_max_flow(&graph, (1, 1).into(), (0, 0).into(), |_| u32::MAX),
+            2 * u32::MAX as u64
+        );
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(5, 5));
+        assert_eq!(
+            grid_max_flow(&graph, (1, 1).into(), (3, 3).into(), |_| u32::MAX),
+            4 * u32::MAX as u64
+        );
+    }
+
+    #[test]
+    fn grid_max_flow_shared_capacity() {
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(2, 2));
+        assert_eq!(
+            grid_max_flow(&graph, (0, 0).into(), (1, 1).into(), |_| 3),
+            6
+        );
+        assert_eq!(
+            grid_max_flow(&graph, (0, 0).into(), (1, 0).into(), |_| 3),
+            6
+        );
+    }
+}
@@ -0,0 +1,25 @@
+//! Algorithms for lattice graphs.
+//! Most of them are generic over the traits of [`petgraph::visit`], so they can be used for any graph in this crate.
+//! The costs of the path finding are generic over [`Measure`](`petgraph::algo::Measure`) as in [`petgraph::algo`],
+//! so integers, or floats wrapped to be ordered, can be used.
+
+mod astar;
+pub use astar::*;
+mod bfs;
+pub use bfs::*;
+mod bridges;
+pub use bridges::*;
+mod components;
+pub use components::*;
+mod dijkstra;
+pub use dijkstra::*;
+mod equidistant;
+pub use equidistant::*;
+mod flow;
+pub use flow::*;
+mod movement;
+pub use movement::*;
+mod region;
+pub use region::*;
+mod visibility;
+pub use visibility::*;
@@ -0,0 +1,71 @@
+use std::hash::Hash;
+
+use petgraph::{
+    algo::{dijkstra, Measure},
+    visit::{IntoEdges, IntoNodeIdentifiers, Visitable},
+};
+
+/**
+Returns the nodes whose shortest path cost from `start` is in `(min_budget, max_budget]`,
+i.e. the nodes which become reachable when the budget is increased from `min_budget` to `max_budget`.
+```
+# use lattice_graph::algo::movement_ring;
+# use lattice_graph::lattice_abstract::square::*;
+# use petgraph::visit::EdgeRef;
+let graph = SquareGraphAbstract::<(), u32>::new_with(SquareShape::new(5, 1), |_| (), |_, _| 1);
+let ring = movement_ring(&graph, (0, 0).into(), 1, 3, |e| *e.weight());
+assert_eq!(ring, vec![SquareOffset::from((2, 0)), SquareOffset::from((3, 0))]);
+```
+*/
+pub fn movement_ring<G, F, K>(
+    graph: G,
+    start: G::NodeId,
+    min_budget: K,
+    max_budget: K,
+    edge_cost: F,
+) -> Vec<G::NodeId>
+where
+    G: IntoEdges + IntoNodeIdentifiers + Visitable,
+    G::NodeId: Eq + Hash,
+    F: FnMut(G::EdgeRef) -> K,
+    K: Measure + Copy,
+{
+    let costs = dijkstra(graph, start, None, edge_cost);
+    graph
+        .node_identifiers()
+        .filter(|n| match costs.get(n) {
+            Some(c) => min_budget < *c && *c <= max_budget,
+            None => false,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice_abstract::square::*;
+    use petgraph::visit::EdgeRef;
+
+    #[test]
+    fn movement_ring_uniform() {
+        let graph =
+            SquareGraphAbstract::<(), u32>::new_with(SquareShape::new(5, 5), |_| (), |_, _| 1);
+        let start = SquareOffset::from((2, 2));
+        let ring = movement_ring(&graph, start, 1, 2, |e| *e.weight());
+        let expected: Vec<_> = graph
+            .node_identifiers()
+            .filter(|n| {
+                let dh = (n.0.horizontal as isize - 2).abs();
+                let dv = (n.0.vertical as isize - 2).abs();
+                dh + dv == 2
+            })
+            .collect();
+        assert_eq!(ring.len(), 8);
+        assert_eq!(ring, expected);
+        assert!(movement_ring(&graph, start, 2, 2, |e| *e.weight()).is_empty());
+        assert_eq!(
+            movement_ring(&graph, start, 0, 0, |e| *e.weight()),
+            Vec::<SquareOffset>::new()
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::{collections::HashSet, hash::Hash};
+
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+
+use crate::lattice_abstract::{shapes::*, LatticeGraph};
+
+/**
+Returns the perimeter of the region, the count of the edges between a node in the region and
+a node out of the region or out of the graph.
+Coordinates in the region which are not in the graph are ignored.
+```
+# use lattice_graph::algo::region_perimeter;
+# use lattice_graph::lattice_abstract::square::*;
+# use std::collections::HashSet;
+let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(4, 4));
+let region: HashSet<SquareOffset> = [(0, 0), (0, 1)].iter().map(|&c| c.into()).collect();
+assert_eq!(region_perimeter(&graph, &region), 6);
+```
+*/
+pub fn region_perimeter<N, E, S>(
+    graph: &LatticeGraph<N, E, S>,
+    region: &HashSet<S::Coordinate>,
+) -> usize
+where
+    S: Shape,
+    S::Coordinate: Eq + Hash,
+{
+    let s = graph.shape();
+    region
+        .iter()
+        .filter(|c| s.to_offset(**c).is_ok())
+        .map(|c| {
+            (0..S::Axis::UNDIRECTED_COUNT)
+                .filter(|d| {
+                    let d = unsafe { <S::Axis as Axis>::Direction::dir_from_index_unchecked(*d) };
+                    match s.move_coord(*c, d) {
+                        Ok(t) => !region.contains(&t),
+                        Err(_) => true,
+                    }
+                })
+                .count()
+        })
+        .sum()
+}
+
+/**
+Returns the ids of the edges which have one end in `region_a` and the other end in `region_b`,
+in the order of [`edge_references`](`IntoEdgeReferences::edge_references`).
+```
+# use lattice_graph::algo::border_edges;
+# use lattice_graph::lattice_abstract::square::*;
+# use std::collections::HashSet;
+let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(2, 1));
+let a: HashSet<SquareOffset> = [SquareOffset::from((0, 0))].iter().copied().collect();
+let b: HashSet<SquareOffset> = [SquareOffset::from((1, 0))].iter().copied().collect();
+assert_eq!(border_edges(&graph, &a, &b), vec![((0, 0).into(), SquareAxis::X)]);
+```
+*/
+pub fn border_edges<N, E, S, C, A, D>(
+    graph: &LatticeGraph<N, E, S>,
+    region_a: &HashSet<C>,
+    region_b: &HashSet<C>,
+) -> Vec<(C, A)>
+where
+    S: Shape<Coordinate = C, Axis = A>,
+    C: Copy + Eq + Hash,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+{
+    graph
+        .edge_references()
+        .filter(|e| {
+            let (s, t) = (e.source(), e.target());
+            (region_a.contains(&s) && region_b.contains(&t))
+                || (region_b.contains(&s) && region_a.contains(&t))
+        })
+        .map(|e| e.id())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex::axial_based::{HexAxial, HexAxialShape, HexGraph};
+    use crate::lattice_abstract::square::*;
+
+    #[test]
+    fn region_perimeter_square() {
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(4, 4));
+        let block: HashSet<SquareOffset> = [(1, 1), (1, 2), (2, 1), (2, 2)]
+            .iter()
+            .map(|&c| c.into())
+            .collect();
+        assert_eq!(region_perimeter(&graph, &block), 8);
+        let corner: HashSet<SquareOffset> = [(0, 0), (0, 1), (1, 0), (1, 1)]
+            .iter()
+            .map(|&c| c.into())
+            .collect();
+        assert_eq!(region_perimeter(&graph, &corner), 8);
+        let all: HashSet<SquareOffset> = (0..16).map(|i| graph.shape().from_index(i)).collect();
+        assert_eq!(region_perimeter(&graph, &all), 16);
+        assert_eq!(region_perimeter(&graph, &HashSet::new()), 0);
+    }
+
+    #[test]
+    fn region_perimeter_hex() {
+        let graph = HexGraph::<(), ()>::new_with(HexAxialShape::new(5, 5), |_| (), |_, _| ());
+        let region: HashSet<HexAxial> = [HexAxial::new(1, 2), HexAxial::new(2, 2)]
+            .iter()
+            .copied()
+            .collect();
+        assert_eq!(region_perimeter(&graph, &region), 10);
+    }
+
+    #[test]
+    fn border_edges_halves() {
+        let graph = SquareGraphAbstract::<(), ()>::new(SquareShape::new(4, 3));
+        let (left, right): (Vec<SquareOffset>, Vec<SquareOffset>) = (0..12)
+            .map(|i| graph.shape().from_index(i))
+            .partition(|c| c.0.horizontal < 2);
+        let left: HashSet<_> = left.into_iter().collect();
+        let right: HashSet<_> = right.into_iter().collect();
+        let seam = border_edges(&graph, &left, &right);
+        assert_eq!(
+            seam,
+            (0..3)
+                .map(|v| (SquareOffset::from((1, v)), SquareAxis::X))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(border_edges(&graph, &right, &left), seam);
+        assert!(!border_edges(&graph, &left, &left).is_empty());
+        assert!(border_edges(&graph, &left, &HashSet::new()).is_empty());
+    }
+}
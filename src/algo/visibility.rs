@@ -0,0 +1,146 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::Hash,
+};
+
+use petgraph::{data::DataMap, visit::IntoNeighbors};
+
+use crate::lattice_abstract::{
+    shapes::{Axis, AxisDirection, Shape},
+    square::{SquareIndex, SquareOffset},
+    LatticeGraph, Offset,
+};
+
+/**
+Returns the nodes within `range` steps from `origin` which are in the line of sight from `origin`.
+
+The line of sight is blocked when a node between them is opaque.
+The opaque node itself can be seen.
+The line is the Bresenham line on the [`Offset`] of the nodes,
+so this only accepts the square shapes, whose coordinate is [`SquareOffset`].
+```
+# use lattice_graph::algo::visible_in_range;
+# use lattice_graph::lattice_abstract::square::*;
+let graph = SquareGraphAbstract::<bool, ()>::new_with(SquareShape::new(5, 1), |c| c == (2, 0), |_, _| ());
+let visible = visible_in_range(&graph, (0, 0).into(), 3, |_, wall| *wall);
+assert_eq!(visible.len(), 3);
+assert!(visible.contains(&SquareOffset::from((2, 0))));
+```
+*/
+pub fn visible_in_range<N, E, S, Ix, D, F>(
+    graph: &LatticeGraph<N, E, S>,
+    origin: SquareOffset<Ix>,
+    range: usize,
+    mut is_opaque: F,
+) -> HashSet<SquareOffset<Ix>>
+where
+    S: Shape<Coordinate = SquareOffset<Ix>>,
+    Ix: SquareIndex,
+    SquareOffset<Ix>: Hash,
+    S::Axis: Axis<Direction = D>,
+    D: AxisDirection + Clone,
+    F: FnMut(SquareOffset<Ix>, &N) -> bool,
+{
+    let mut visible = HashSet::new();
+    let s = graph.shape();
+    let o = match s.to_offset(origin) {
+        Ok(o) => o,
+        Err(_) => return visible,
+    };
+    let mut in_range = HashSet::new();
+    let mut queue = VecDeque::new();
+    in_range.insert(origin);
+    queue.push_back((origin, 0));
+    while let Some((c, d)) = queue.pop_front() {
+        if d == range {
+            continue;
+        }
+        for n in graph.neighbors(c) {
+            if in_range.insert(n) {
+                queue.push_back((n, d + 1));
+            }
+        }
+    }
+    for c in in_range {
+        let clear = offset_line(o, c.offset()).all(|m| {
+            let m = SquareOffset::new(m.horizontal, m.vertical);
+            matches!(graph.node_weight(m), Some(n) if !is_opaque(m, n))
+        });
+        if clear {
+            visible.insert(c);
+        }
+    }
+    visible
+}
+
+/// Iterate the offsets on the Bresenham line between `a` and `b`, excluding both ends.
+fn offset_line(a: Offset, b: Offset) -> impl Iterator<Item = Offset> {
+    let (x0, y0) = (a.horizontal as isize, a.vertical as isize);
+    let (x1, y1) = (b.horizontal as isize, b.vertical as isize);
+    let (dx, dy) = ((x1 - x0).abs(), -(y1 - y0).abs());
+    let (sx, sy) = ((x1 - x0).signum(), (y1 - y0).signum());
+    let mut state = (x0, y0, dx + dy);
+    std::iter::from_fn(move || {
+        let (x, y, err) = &mut state;
+        if (*x, *y) == (x1, y1) {
+            return None;
+        }
+        let e2 = 2 * *err;
+        if e2 >= dy {
+            *err += dy;
+            *x += sx;
+        }
+        if e2 <= dx {
+            *err += dx;
+            *y += sy;
+        }
+        Some((*x, *y))
+    })
+    .take_while(move |p| *p != (x1, y1))
+    .map(|(x, y)| Offset::new(x as usize, y as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice_abstract::square::*;
+
+    #[test]
+    fn visible_in_range_wall() {
+        let wall = SquareOffset::from((2, 2));
+        let graph = SquareGraphAbstract::<bool, ()>::new_with(
+            SquareShape::new(5, 5),
+            |c| c == wall,
+            |_, _| (),
+        );
+        let visible = visible_in_range(&graph, (0, 2).into(), 3, |_, w| *w);
+        // blocked by the wall
+        assert!(!visible.contains(&SquareOffset::from((3, 2))));
+        // out of range
+        assert!(!visible.contains(&SquareOffset::from((4, 2))));
+        assert!(!visible.contains(&SquareOffset::from((3, 4))));
+        assert!(visible.contains(&wall));
+        assert!(visible.contains(&SquareOffset::from((0, 2))));
+        assert!(visible.contains(&SquareOffset::from((1, 2))));
+        assert!(visible.contains(&SquareOffset::from((0, 0))));
+        assert!(visible.contains(&SquareOffset::from((2, 3))));
+        assert!(visible.contains(&SquareOffset::from((1, 4))));
+        assert_eq!(
+            visible_in_range(&graph, (0, 2).into(), 3, |_, _| false).len(),
+            5 + 5 + 3 + 1
+        );
+        assert!(visible_in_range(&graph, (5, 0).into(), 3, |_, w| *w).is_empty());
+    }
+
+    #[test]
+    fn offset_line_excludes_ends() {
+        let line = offset_line(Offset::new(0, 0), Offset::new(3, 1)).collect::<Vec<_>>();
+        assert_eq!(line.len(), 2);
+        assert!(offset_line(Offset::new(1, 1), Offset::new(1, 1))
+            .next()
+            .is_none());
+        assert!(offset_line(Offset::new(1, 1), Offset::new(2, 2))
+            .next()
+            .is_none());
+    }
+}
@@ -18,9 +18,9 @@ pub struct FixedVec2D<T> {
 
 impl<T> FixedVec2D<T> {
     /// Creates a array2d with a vec.
-    /// Returns [`None`] if `h * v != vec.len()`
+    /// Returns [`None`] if `h * v != vec.len()` or `h * v` overflows.
     pub unsafe fn from_raw(h: NonZeroUsize, v: usize, vec: Vec<T>) -> Option<Self> {
-        if h.get() * v != vec.len() {
+        if h.get().checked_mul(v) != Some(vec.len()) {
             None
         } else {
             Some(Self::from_raw_unchecked(h, v, vec))
@@ -103,6 +103,12 @@ impl<T> FixedVec2D<T> {
         self.h_size() * self.v_size()
     }
 
+    /// Returns the bytes allocated on the heap, which is the values and the heads pointing to each horizontal index.
+    /// The heap memory owned by the values is not counted.
+    pub fn memory_usage(&self) -> usize {
+        self.size() * mem::size_of::<T>() + self.h_size() * mem::size_of::<&mut [T]>()
+    }
+
     /// Returns the slice of all values in the array.
     #[inline]
     pub fn ref_1d(&self) -> &[T] {
@@ -135,6 +141,49 @@ impl<T> FixedVec2D<T> {
         unsafe { slice::from_raw_parts_mut(self.heads.cast().as_mut(), self.h_size()) }
     }
 
+    /**
+    Creates a new array with the same size by applying `f` to each value.
+    ```
+    # use std::num::NonZeroUsize;
+    # use lattice_graph::fixedvec2d::FixedVec2D;
+    let array = FixedVec2D::new(NonZeroUsize::new(3).unwrap(), 2, |h, v| h * 2 + v);
+    let mapped = array.map(|x| x * 10);
+    assert_eq!(mapped.ref_2d()[2][1], 50);
+    ```
+    */
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> FixedVec2D<U> {
+        let vec = self.ref_1d().iter().map(f).collect::<Vec<_>>();
+        // SAFETY : the length of `vec` is same as `self`.
+        unsafe { FixedVec2D::from_raw_unchecked(self.hsize, self.v_size(), vec) }
+    }
+
+    /// Creates a new array with the same size by applying `f` to each pair of the values at the same position.
+    ///
+    /// # Panics
+    /// Panics if the size of `other` is different from `self`.
+    pub fn zip_map<U, V, F: FnMut(&T, &U) -> V>(
+        &self,
+        other: &FixedVec2D<U>,
+        mut f: F,
+    ) -> FixedVec2D<V> {
+        assert!(
+            self.h_size() == other.h_size() && self.v_size() == other.v_size(),
+            "size mismatch: {}x{} and {}x{}",
+            self.h_size(),
+            self.v_size(),
+            other.h_size(),
+            other.v_size()
+        );
+        let vec = self
+            .ref_1d()
+            .iter()
+            .zip(other.ref_1d())
+            .map(|(a, b)| f(a, b))
+            .collect::<Vec<_>>();
+        // SAFETY : the length of `vec` is same as `self`.
+        unsafe { FixedVec2D::from_raw_unchecked(self.hsize, self.v_size(), vec) }
+    }
+
     /// Returns the underlying [`Vec`] consuming this [`FixedVec2D`]
     pub fn into_raw(self) -> Vec<T> {
         unsafe { ManuallyDrop::new(self).into_raw_inner(true) }
@@ -301,6 +350,110 @@ impl<T> IndexMut<(usize, usize)> for FixedVec2D<T> {
     }
 }
 
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::FixedVec2D;
+    use serde::{
+        de::{self, MapAccess, SeqAccess, Visitor},
+        ser::SerializeStruct,
+        Deserialize, Deserializer, Serialize, Serializer,
+    };
+    use std::{fmt, marker::PhantomData, num::NonZeroUsize};
+
+    const FIELDS: &[&str] = &["h_size", "v_size", "data"];
+
+    impl<T: Serialize> Serialize for FixedVec2D<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut s = serializer.serialize_struct("FixedVec2D", 3)?;
+            s.serialize_field("h_size", &self.hsize)?;
+            s.serialize_field("v_size", &self.v_size())?;
+            s.serialize_field("data", self.ref_1d())?;
+            s.end()
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(field_identifier, rename_all = "snake_case")]
+    enum Field {
+        HSize,
+        VSize,
+        Data,
+    }
+
+    struct FixedVec2DVisitor<T>(PhantomData<fn() -> T>);
+
+    fn build<T, E: de::Error>(h: NonZeroUsize, v: usize, data: Vec<T>) -> Result<FixedVec2D<T>, E> {
+        let len = data.len();
+        if h.get().checked_mul(v) != Some(len) {
+            return Err(E::invalid_length(
+                len,
+                &"data with the length of `h_size * v_size`",
+            ));
+        }
+        // SAFETY : the length is checked above.
+        Ok(unsafe { FixedVec2D::from_raw_unchecked(h, v, data) })
+    }
+
+    impl<'de, T: Deserialize<'de>> Visitor<'de> for FixedVec2DVisitor<T> {
+        type Value = FixedVec2D<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("struct FixedVec2D")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let h = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+            let v = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+            let data = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+            build(h, v, data)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut h = None;
+            let mut v = None;
+            let mut data = None;
+            while let Some(key) = map.next_key()? {
+                match key {
+                    Field::HSize => {
+                        if h.is_some() {
+                            return Err(de::Error::duplicate_field("h_size"));
+                        }
+                        h = Some(map.next_value()?);
+                    }
+                    Field::VSize => {
+                        if v.is_some() {
+                            return Err(de::Error::duplicate_field("v_size"));
+                        }
+                        v = Some(map.next_value()?);
+                    }
+                    Field::Data => {
+                        if data.is_some() {
+                            return Err(de::Error::duplicate_field("data"));
+                        }
+                        data = Some(map.next_value()?);
+                    }
+                }
+            }
+            let h = h.ok_or_else(|| de::Error::missing_field("h_size"))?;
+            let v = v.ok_or_else(|| de::Error::missing_field("v_size"))?;
+            let data = data.ok_or_else(|| de::Error::missing_field("data"))?;
+            build(h, v, data)
+        }
+    }
+
+    impl<'de, T: Deserialize<'de>> Deserialize<'de> for FixedVec2D<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_struct("FixedVec2D", FIELDS, FixedVec2DVisitor(PhantomData))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     type Nz = std::num::NonZeroUsize;
@@ -338,6 +491,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn from_raw_overflow() {
+        let x = unsafe { FixedVec2D::from_raw(Nz::new(2).unwrap(), usize::MAX / 2 + 1, vec![()]) };
+        assert!(x.is_none());
+        let x = unsafe { FixedVec2D::from_raw(Nz::new(2).unwrap(), 3, vec![0; 6]) };
+        assert_eq!(x.map(|x| x.ref_2d()[1].len()), Some(3));
+    }
+
     #[test]
     fn gen_zst() {
         let x = FixedVec2D::new(Nz::new(5).unwrap(), 2, |_h, _v| ());
@@ -384,4 +545,100 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn map() {
+        let x = FixedVec2D::new(Nz::new(5).unwrap(), 2, |h, v| (h, v));
+        let y = x.map(|&(h, v)| h * 10 + v);
+        assert_eq!((y.h_size(), y.v_size()), (5, 2));
+        for i in 0..5 {
+            for j in 0..2 {
+                assert_eq!(y.ref_2d()[i][j], i * 10 + j);
+            }
+        }
+        let z = x.zip_map(&y, |&(h, v), &n| h + v + n);
+        assert_eq!(z.ref_2d()[3][1], 3 + 1 + 31);
+        assert_eq!(z.ref_1d().len(), 10);
+    }
+
+    #[test]
+    fn memory_usage() {
+        let x = FixedVec2D::new(Nz::new(5).unwrap(), 2, |_, _| 0u32);
+        assert_eq!(
+            x.memory_usage(),
+            5 * 2 * 4 + 5 * 2 * std::mem::size_of::<usize>()
+        );
+        let x = FixedVec2D::new(Nz::new(5).unwrap(), 2, |_, _| ());
+        assert_eq!(x.memory_usage(), 5 * 2 * std::mem::size_of::<usize>());
+    }
+
+    #[test]
+    fn map_zst() {
+        let x = FixedVec2D::new(Nz::new(4).unwrap(), 0, |h, v| (h, v));
+        let y = x.map(|_| ());
+        assert_eq!((y.h_size(), y.v_size()), (4, 0));
+        assert!(y.ref_2d().iter().all(|c| c.is_empty()));
+        let z = y.zip_map(&x, |_, _| 1u8);
+        assert_eq!((z.h_size(), z.v_size()), (4, 0));
+
+        let x = FixedVec2D::new(Nz::new(3).unwrap(), 2, |_, _| ());
+        let y = x.map(|_| ());
+        assert_eq!((y.h_size(), y.v_size()), (3, 2));
+        let z = y.zip_map(&x, |_, _| String::from("a"));
+        assert_eq!(z.ref_2d()[2][1], "a");
+        assert_eq!(z.ref_1d().len(), 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zip_map_size_mismatch() {
+        let x = FixedVec2D::new(Nz::new(3).unwrap(), 2, |_, _| 0);
+        let y = FixedVec2D::new(Nz::new(2).unwrap(), 3, |_, _| 0);
+        x.zip_map(&y, |a, b| a + b);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_json_map() {
+        let x = FixedVec2D::new(Nz::new(3).unwrap(), 2, |h, v| (h * 2 + v) as u32);
+        let json = serde_json::to_string(&x).unwrap();
+        assert_eq!(json, r#"{"h_size":3,"v_size":2,"data":[0,1,2,3,4,5]}"#);
+        assert_eq!(serde_json::from_str::<FixedVec2D<u32>>(&json).unwrap(), x);
+        let reordered = r#"{"data":[0,1,2,3,4,5],"v_size":2,"h_size":3}"#;
+        assert_eq!(
+            serde_json::from_str::<FixedVec2D<u32>>(reordered).unwrap(),
+            x
+        );
+        let wrong_len = r#"{"h_size":3,"v_size":2,"data":[0,1,2]}"#;
+        assert!(serde_json::from_str::<FixedVec2D<u32>>(wrong_len).is_err());
+        let missing = r#"{"h_size":3,"data":[]}"#;
+        assert!(serde_json::from_str::<FixedVec2D<u32>>(missing).is_err());
+        let zero = r#"{"h_size":0,"v_size":2,"data":[]}"#;
+        assert!(serde_json::from_str::<FixedVec2D<u32>>(zero).is_err());
+        let overflow = format!(
+            r#"{{"h_size":2,"v_size":{},"data":[]}}"#,
+            usize::MAX / 2 + 1
+        );
+        assert!(serde_json::from_str::<FixedVec2D<u32>>(&overflow).is_err());
+        let overflow = format!(
+            r#"{{"h_size":2,"v_size":{},"data":[]}}"#,
+            usize::MAX / 2 + 1
+        );
+        assert!(serde_json::from_str::<FixedVec2D<()>>(&overflow).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_bincode_seq() {
+        let x = FixedVec2D::new(Nz::new(4).unwrap(), 3, |h, v| (h, v as u8));
+        let bytes = bincode::serialize(&x).unwrap();
+        let y: FixedVec2D<(usize, u8)> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(x, y);
+        let empty = FixedVec2D::new(Nz::new(2).unwrap(), 0, |_, _| 0u8);
+        let bytes = bincode::serialize(&empty).unwrap();
+        assert_eq!(
+            bincode::deserialize::<FixedVec2D<u8>>(&bytes).unwrap(),
+            empty
+        );
+    }
 }
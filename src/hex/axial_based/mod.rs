@@ -44,6 +44,10 @@ pub(crate) mod shapes;
 pub use super::shapes::*;
 pub use crate::lattice_abstract::shapes::*;
 use crate::lattice_abstract::LatticeGraph;
+use petgraph::{
+    visit::IntoEdgesDirected,
+    Direction::{Incoming, Outgoing},
+};
 pub use shapes::{ConstHexAxialShape, HexAxial, HexAxialShape};
 /// Coordinate for Hex Graph with axial coordinates.
 pub type Coord = HexAxial;
@@ -63,6 +67,15 @@ pub type HexGraphLoopEW<N, E, B = OddR, H = usize, V = usize> =
 #[cfg(feature = "const-generic-wrap")]
 pub type HexGraphConstLoopEW<N, E, B, const H: usize, const V: usize> =
     LatticeGraph<N, E, ConstHexAxialShape<B, LoopEW, H, V>>;
+///Hex Graph with axial coordinates with n-s loop. See [`LoopNS`] for the constraint of the size.
+pub type HexGraphLoopNS<N, E, B = OddQ, H = usize, V = usize> =
+    LatticeGraph<N, E, HexAxialShape<B, LoopNS, H, V>>;
+
+///Hex Graph with axial coordinates with n-s loop. See [`LoopNS`] for the constraint of the size.
+#[cfg(feature = "const-generic-wrap")]
+pub type HexGraphConstLoopNS<N, E, B, const H: usize, const V: usize> =
+    LatticeGraph<N, E, ConstHexAxialShape<B, LoopNS, H, V>>;
+
 ///Directed Hex Graph with axial coordinates.
 pub type DiHexGraph<N, E, B = OddR, Loop = (), H = usize, V = usize> =
     LatticeGraph<N, E, HexAxialShape<DirectedMarker<B>, Loop, H, V>>;
@@ -71,6 +84,110 @@ pub type DiHexGraph<N, E, B = OddR, Loop = (), H = usize, V = usize> =
 pub type DiHexGraphConst<N, E, B, Loop, const H: usize, const V: usize> =
     LatticeGraph<N, E, ConstHexAxialShape<DirectedMarker<B>, Loop, H, V>>;
 
+impl<N, E, B, L, H, V, HA> LatticeGraph<N, E, HexAxialShape<B, L, H, V, HA>>
+where
+    HA: shapes::AxialCoord,
+    HexAxialShape<B, L, H, V, HA>: Shape<Coordinate = HA>,
+{
+    /// Iterate the coordinates in hex spiral order like [`HexAxial::spiral_coords`],
+    /// skipping the coordinates which are not in this graph.
+    pub fn spiral_coords(&self, center: HA, max_radius: usize) -> impl Iterator<Item = HA> + '_ {
+        shapes::axial_spiral(center, max_radius).filter(move |c| self.shape().to_offset(*c).is_ok())
+    }
+
+    /// Iterate the coordinates which is `radius` away from `center`,
+    /// skipping the coordinates which are not in this graph.
+    /// It starts from the `W` (or `SW`) corner and goes clockwise, and yields only `center` if `radius` is 0.
+    pub fn ring_coords(&self, center: HA, radius: usize) -> impl Iterator<Item = HA> + '_ {
+        shapes::axial_ring(center, radius).filter(move |c| self.shape().to_offset(*c).is_ok())
+    }
+
+    /// Iterate the coordinates on the straight line from `a` to `b` like [`HexAxial::line_coords`],
+    /// stopping at the first coordinate which is not in this graph.
+    pub fn line_in_bounds(&self, a: HA, b: HA) -> impl Iterator<Item = HA> + '_ {
+        shapes::axial_line(a, b).take_while(move |c| self.shape().to_offset(*c).is_ok())
+    }
+}
+
+impl<N, E, B, L, H, V, HA, A> LatticeGraph<N, E, HexAxialShape<B, L, H, V, HA>>
+where
+    HexAxialShape<B, L, H, V, HA>: Shape<Coordinate = HA, Axis = A>,
+    HA: Copy,
+    A: Axis<Direction = AxisDR>,
+{
+    /// Iterate the neighbors of the point top hex in the clockwise order from the north east,
+    /// that is `NE`, `E`, `SE`, `SW`, `W`, `NW` as shown in the diagram of the [module](`self`).
+    /// For `C = (0, 1)` in the diagram of [`OddR`], it is `(0, 2)`, `(1, 1)`, `(1, 0)`, `(0, 0)`, `(-1, 1)`, `(-1, 2)`.
+    /// The neighbors not in the graph are skipped.
+    pub fn neighbors_oriented(&self, c: HA) -> impl Iterator<Item = HA> + '_ {
+        let valid = self.shape().to_offset(c).is_ok();
+        IntoIterator::into_iter([
+            AxisDR::NE,
+            AxisDR::E,
+            AxisDR::SE,
+            AxisDR::SW,
+            AxisDR::W,
+            AxisDR::NW,
+        ])
+        .filter(move |_| valid)
+        .filter_map(move |d| self.shape().move_coord(c, d).ok())
+    }
+
+    /**
+    Render the nodes as a grid of characters for debugging, like [`SquareGraph::debug_grid`](`crate::SquareGraph::debug_grid`).
+    Each line is a row of the same `q` and the nodes are separated by a space.
+    The row with `q = 0` is placed at the bottom, and the rows shifted to the east
+    (odd rows for [`OddR`] and even rows for [`EvenR`]) are indented by a space.
+    ```
+    # use lattice_graph::hex::axial_based::*;
+    let graph = HexGraph::<_, (), OddR>::new_with(HexAxialShape::new(3, 2), |c| c.r(), |_, _| ());
+    assert_eq!(graph.debug_grid(|r| if *r == 0 { '#' } else { '.' }), " # . .\n# . .\n");
+    ```
+    */
+    pub fn debug_grid(&self, cell: impl Fn(&N) -> char) -> String
+    where
+        B: OE,
+    {
+        let h = self.shape().horizontal();
+        let v = self.shape().vertical();
+        let mut s = String::with_capacity(2 * (h + 1) * v);
+        for vi in (0..v).rev() {
+            if (vi + B::CONVERT_OFFSET) % 2 == 1 {
+                s.push(' ');
+            }
+            for hi in 0..h {
+                if hi != 0 {
+                    s.push(' ');
+                }
+                // SAFETY : the offset is in the range of the shape.
+                s.push(cell(unsafe {
+                    self.node_weight_unchecked_raw(Offset::new(hi, vi))
+                }));
+            }
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl<N, E, B, L, H, V, HA, A, D> LatticeGraph<N, E, HexAxialShape<DirectedMarker<B>, L, H, V, HA>>
+where
+    HexAxialShape<DirectedMarker<B>, L, H, V, HA>: Shape<Coordinate = HA, Axis = A>,
+    HA: Copy,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+{
+    /// Returns the count of the edges coming into the node.
+    pub fn in_degree(&self, c: HA) -> usize {
+        self.edges_directed(c, Incoming).count()
+    }
+
+    /// Returns the count of the edges going out from the node.
+    pub fn out_degree(&self, c: HA) -> usize {
+        self.edges_directed(c, Outgoing).count()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{array::IntoIter, mem};
@@ -180,4 +297,828 @@ mod tests {
         let e = graph.neighbors(target);
         debug_assert!(e.eq(neighbors));
     }
+
+    #[rstest]
+    #[case(0)]
+    #[case(1)]
+    #[case(2)]
+    #[case(5)]
+    fn spiral_coords(hexgraph_oddr55: Hex5x5, #[case] r: usize) {
+        let center = C::new(3, -2);
+        let spiral: Vec<_> = C::spiral_coords(center, r).collect();
+        assert_eq!(spiral.len(), 1 + 3 * r * (r + 1));
+        assert_eq!(spiral[0], center);
+        let set: std::collections::HashSet<_> = spiral.iter().collect();
+        assert_eq!(set.len(), spiral.len());
+        // each step on the ring moves to the next neighbor.
+        let graph = hexgraph_oddr55;
+        let ring1: Vec<_> = C::spiral_coords(C::new(1, 2), 1).skip(1).collect();
+        for (a, b) in ring1.iter().zip(ring1.iter().cycle().skip(1)) {
+            assert!(graph.shape().is_neighbor(*a, *b));
+        }
+    }
+
+    #[rstest]
+    fn spiral_coords_clamped(hexgraph_oddr55: Hex5x5) {
+        let graph = hexgraph_oddr55;
+        let center = C::new(0, 0);
+        let spiral: Vec<_> = graph.spiral_coords(center, 2).collect();
+        assert_eq!(spiral[0], center);
+        assert!(spiral.len() < 1 + 3 * 2 * 3);
+        assert!(spiral.iter().all(|c| graph.node_weight(*c).is_some()));
+        assert_eq!(
+            spiral.len(),
+            C::spiral_coords(center, 2)
+                .filter(|c| graph.node_weight(*c).is_some())
+                .count()
+        );
+        assert_eq!(graph.spiral_coords(C::new(1, 2), 9).count(), 25);
+    }
+
+    fn assert_connectivity_cache<N, E, S>(graph: LatticeGraph<N, E, S>)
+    where
+        N: Clone,
+        E: Clone + PartialEq + std::fmt::Debug,
+        S: Shape + Clone,
+        S::Coordinate: PartialEq + std::fmt::Debug,
+        S::Axis: PartialEq + std::fmt::Debug,
+        <S::Axis as Axis>::Direction: Copy + PartialEq + std::fmt::Debug,
+    {
+        let cached = graph.clone().with_connectivity_cache();
+        assert!(cached.has_connectivity_cache());
+        for i in 0..graph.node_count() {
+            let c = graph.from_index(i);
+            assert!(graph.neighbors(c).eq(cached.neighbors(c)));
+            assert!(graph.edges(c).eq(cached.edges(c)));
+        }
+        assert!(graph.edge_references().eq(cached.edge_references()));
+    }
+
+    #[rstest]
+    fn connectivity_cache(
+        hexgraph_oddr55: Hex5x5,
+        hexgraph_oddr55_lew: Hex5x5Lew,
+        hexgraph_evenq55: Hex5x5EQ,
+    ) {
+        assert_connectivity_cache(hexgraph_oddr55);
+        assert_connectivity_cache(hexgraph_oddr55_lew);
+        assert_connectivity_cache(hexgraph_evenq55);
+    }
+
+    #[test]
+    fn hex_offset_conversion() {
+        use crate::hex::{double_coord::DoubleCoord, offset_based::HexOffset};
+        use std::convert::TryFrom;
+        fn check<B: shapes::HexAxialShapeBase<C>>() {
+            let s = HexAxialShape::<B, (), usize, usize>::new(6, 5);
+            for i in 0..s.node_count() {
+                let o = s.index_to_offset(i);
+                let ho = HexOffset::new(o.horizontal(), o.vertical());
+                let c = C::from_hex_offset::<B>(ho);
+                assert_eq!(c, s.from_offset(o));
+                assert_eq!(c.to_hex_offset::<B>(), Some(ho));
+            }
+            for r in -4..4 {
+                for q in -4..4 {
+                    let c = C::new(r, q);
+                    if let Some(o) = c.to_hex_offset::<B>() {
+                        assert_eq!(C::from_hex_offset::<B>(o), c);
+                    }
+                }
+            }
+        }
+        check::<OddR>();
+        check::<EvenR>();
+        check::<OddQ>();
+        check::<EvenQ>();
+        assert_eq!(C::new(0, -1).to_hex_offset::<OddR>(), None);
+        assert_eq!(C::new(-1, 1).to_hex_offset::<OddR>(), None);
+        assert_eq!(
+            C::new(-1, 1).to_hex_offset::<EvenR>(),
+            Some(HexOffset::new(0, 1))
+        );
+
+        for h in 0..6 {
+            for v in 0..5 {
+                let o = HexOffset::new(h, v);
+                let d = DoubleCoord::from(o);
+                assert_eq!(HexOffset::from(d), o);
+                let c = C::from(d);
+                assert_eq!(c, C::from_hex_offset::<OddR>(o));
+                assert_eq!(DoubleCoord::try_from(c), Ok(d));
+            }
+        }
+        assert!(DoubleCoord::try_from(C::new(-1, 1)).is_err());
+    }
+
+    #[test]
+    fn line_coords() {
+        let line: Vec<_> = C::line_coords(C::new(0, 0), C::new(2, 1)).collect();
+        assert_eq!(
+            line,
+            [C::new(0, 0), C::new(1, 0), C::new(1, 1), C::new(2, 1)]
+        );
+        let line: Vec<_> = C::line_coords(C::new(1, 2), C::new(1, -2)).collect();
+        assert_eq!(
+            line,
+            [
+                C::new(1, 2),
+                C::new(1, 1),
+                C::new(1, 0),
+                C::new(1, -1),
+                C::new(1, -2)
+            ]
+        );
+        assert!(C::line_coords(C::new(3, 1), C::new(3, 1)).eq(core::iter::once(C::new(3, 1))));
+        for &(a, b) in &[
+            (C::new(0, 0), C::new(5, -3)),
+            (C::new(-2, 4), C::new(3, 3)),
+            (C::new(4, -1), C::new(-3, 0)),
+        ] {
+            let line: Vec<_> = C::line_coords(a, b).collect();
+            assert_eq!(line.len(), a.distance(&b) + 1);
+            assert_eq!(line[0], a);
+            assert_eq!(line[line.len() - 1], b);
+            assert!(line.windows(2).all(|w| w[0].distance(&w[1]) == 1));
+        }
+    }
+
+    #[rstest]
+    fn line_in_bounds(hexgraph_oddr55: Hex5x5) {
+        let graph = hexgraph_oddr55;
+        let (a, b) = (C::new(0, 0), C::new(0, 4));
+        assert!(graph.line_in_bounds(a, b).eq(C::line_coords(a, b)));
+        // (0, -1) is out of the graph
+        assert!(graph
+            .line_in_bounds(C::new(0, 1), C::new(0, -3))
+            .eq(IntoIterator::into_iter([C::new(0, 1), C::new(0, 0)])));
+    }
+
+    #[test]
+    fn ring_coords() {
+        let graph = HexGraphConst::<(), (), OddR, 9, 9>::new_with_s(|_| (), |_, _| ());
+        let center = graph.shape().from_offset(Offset::new(4, 4));
+        assert!(graph.ring_coords(center, 0).eq(core::iter::once(center)));
+        for r in 1..=4 {
+            let ring: Vec<_> = graph.ring_coords(center, r).collect();
+            assert_eq!(ring.len(), 6 * r);
+            assert!(ring.iter().all(|c| c.distance(&center) == r));
+        }
+        assert_eq!(
+            graph.spiral_coords(center, 4).count(),
+            (0..=4)
+                .map(|r| graph.ring_coords(center, r).count())
+                .sum::<usize>()
+        );
+        let corner = C::new(0, 0);
+        for r in 0..6 {
+            let ring: Vec<_> = graph.ring_coords(corner, r).collect();
+            assert!(ring.len() <= 6 * r.max(1));
+            assert!(ring.iter().all(|c| graph.node_weight(*c).is_some()));
+        }
+        assert_eq!(graph.ring_coords(corner, 1).count(), 2);
+    }
+
+    #[test]
+    fn rotate_reflect() {
+        let graph = HexGraphConst::<(), (), OddR, 9, 9>::new_with_s(|_| (), |_, _| ());
+        let center = graph.shape().from_offset(Offset::new(4, 4));
+        let unit = |i: usize| {
+            let n = graph
+                .shape()
+                .move_coord(center, AxisDR::from_index(i % 6).unwrap())
+                .unwrap();
+            C::new(n.r() - center.r(), n.q() - center.q())
+        };
+        for i in 0..6 {
+            assert_eq!(unit(i).rotate_cw(), unit(i + 1));
+            assert_eq!(unit(i + 1).rotate_ccw(), unit(i));
+        }
+        let origin = C::new(0, 0);
+        for c in C::spiral_coords(origin, 3) {
+            let mut r = c;
+            for _ in 0..6 {
+                r = r.rotate_cw();
+                assert_eq!(r.distance(&origin), c.distance(&origin));
+            }
+            assert_eq!(r, c);
+            assert_eq!(c.rotate_cw().rotate_ccw(), c);
+            for f in IntoIterator::into_iter([C::reflect_q, C::reflect_r, C::reflect_s]) {
+                assert_eq!(f(&f(&c)), c);
+                assert_eq!(f(&c).distance(&origin), c.distance(&origin));
+            }
+        }
+        assert_eq!(C::new(1, 2).reflect_q(), C::new(-3, 2));
+        assert_eq!(C::new(1, 2).reflect_r(), C::new(1, -3));
+        assert_eq!(C::new(1, 2).reflect_s(), C::new(2, 1));
+    }
+
+    #[rstest]
+    fn distance(hexgraph_oddr55: Hex5x5, hexgraph_evenq55: Hex5x5EQ) {
+        let c = C::new(1, 2);
+        assert_eq!(c.distance(&c), 0);
+        for n in hexgraph_oddr55.neighbors(c) {
+            assert_eq!(c.distance(&n), 1);
+        }
+        for n in hexgraph_evenq55.neighbors(c) {
+            assert_eq!(n.distance(&c), 1);
+        }
+        assert_eq!(C::new(0, 0).distance(&C::new(3, 0)), 3);
+        assert_eq!(C::new(0, 0).distance(&C::new(2, -2)), 2);
+        assert_eq!(C::new(0, 0).distance(&C::new(2, 2)), 4);
+        assert_eq!(C::new(-1, 3).distance(&C::new(2, -1)), 4);
+        for (i, ring) in C::spiral_coords(c, 3).enumerate() {
+            let r = match i {
+                0 => 0,
+                1..=6 => 1,
+                7..=18 => 2,
+                _ => 3,
+            };
+            assert_eq!(c.distance(&ring), r);
+        }
+    }
+
+    #[test]
+    fn in_out_degree() {
+        type DiHex = DiHexGraphConst<(), (), OddR, (), 5, 5>;
+        type DiHexLew = DiHexGraphConst<(), (), OddR, LoopEW, 5, 5>;
+        let graph = DiHex::new_with_s(|_| (), |_, _| ());
+        for &(c, d) in &[(C::new(0, 0), 2), (C::new(4, 0), 3), (C::new(1, 2), 6)] {
+            assert_eq!(graph.out_degree(c), d);
+            assert_eq!(graph.in_degree(c), d);
+        }
+        let graph = DiHexLew::new_with_s(|_| (), |_, _| ());
+        assert_eq!(graph.out_degree(C::new(0, 0)), 4);
+        assert_eq!(graph.in_degree(C::new(0, 0)), 4);
+        assert!(graph
+            .edges_directed(C::new(0, 0), Incoming)
+            .all(|e| e.target() == C::new(0, 0)));
+        assert_eq!(graph.out_degree(C::new(5, 0)), 0);
+        assert_eq!(graph.in_degree(C::new(5, 0)), 0);
+    }
+
+    #[rstest]
+    fn neighbor_table(hexgraph_oddr55: Hex5x5, hexgraph_oddr55_lew: Hex5x5Lew) {
+        let table = hexgraph_oddr55.neighbor_table();
+        for i in 0..hexgraph_oddr55.node_count() {
+            let c = hexgraph_oddr55.from_index(i);
+            assert!(table.neighbors(c).eq(hexgraph_oddr55.neighbors(c)));
+            assert!(table.neighbors_by_index(i).eq(hexgraph_oddr55.neighbors(c)));
+            assert_eq!(
+                table.get(c, AxisDR::E),
+                hexgraph_oddr55.shape().move_coord(c, AxisDR::E).ok()
+            );
+        }
+        assert_eq!(table.neighbors(C::new(5, 0)).count(), 0);
+        let cached = hexgraph_oddr55.clone().with_connectivity_cache();
+        let c = C::new(2, 2);
+        assert!(cached.neighbor_table().neighbors(c).eq(table.neighbors(c)));
+        let table = hexgraph_oddr55_lew.neighbor_table();
+        for i in 0..hexgraph_oddr55_lew.node_count() {
+            let c = hexgraph_oddr55_lew.from_index(i);
+            assert!(table.neighbors(c).eq(hexgraph_oddr55_lew.neighbors(c)));
+        }
+    }
+
+    #[test]
+    fn debug_grid() {
+        let cell = |c: &C| match (c.r(), c.q()) {
+            (0, 0) => 'o',
+            (0, 1) => 'x',
+            (_, q) => char::from_digit(q as u32, 10).unwrap(),
+        };
+        let graph = HexGraph::<_, (), OddR>::new_with(HexAxialShape::new(3, 2), |c| c, |_, _| ());
+        assert_eq!(graph.debug_grid(cell), " x 1 1\no 0 0\n");
+        let graph = HexGraph::<_, (), EvenR>::new_with(HexAxialShape::new(3, 2), |c| c, |_, _| ());
+        assert_eq!(graph.debug_grid(cell), "1 x 1\n o 0 0\n");
+        let graph =
+            HexGraphLoopEW::<_, (), OddR>::new_with(HexAxialShape::new(2, 3), |c| c, |_, _| ());
+        assert_eq!(graph.debug_grid(cell), "2 2\n x 1\no 0\n");
+    }
+
+    #[rstest]
+    fn valid_directions(hexgraph_oddr55: Hex5x5, hexgraph_oddr55_lew: Hex5x5Lew) {
+        let s = hexgraph_oddr55.shape();
+        let dirs = s.valid_directions(C::new(0, 0)).collect::<Vec<_>>();
+        assert_eq!(dirs, vec![AxisR::NE.foward(), AxisR::E.foward()]);
+        assert_eq!(s.valid_directions(C::new(1, 1)).count(), 6);
+        assert_eq!(s.valid_directions(C::new(5, 0)).count(), 0);
+        // the corner of the loop has the neighbors across the border.
+        let s = hexgraph_oddr55_lew.shape();
+        let dirs = s.valid_directions(C::new(0, 0)).collect::<Vec<_>>();
+        assert_eq!(dirs.len(), 4);
+        for d in dirs {
+            assert!(s.move_coord(C::new(0, 0), d).is_ok());
+        }
+        for i in 0..hexgraph_oddr55_lew.node_count() {
+            let c = s.from_index(i);
+            assert_eq!(s.valid_directions(c).count(), hexgraph_oddr55_lew.degree(c));
+        }
+    }
+
+    #[rstest]
+    fn try_from_index(hexgraph_oddr55: Hex5x5, hexgraph_evenq55: Hex5x5EQ) {
+        let s = hexgraph_oddr55.shape();
+        for i in 0..hexgraph_oddr55.node_count() {
+            let c = s.try_from_index(i);
+            assert_eq!(c, Some(s.from_index(i)));
+            assert_eq!(s.to_index(c.unwrap()), Some(i));
+        }
+        assert_eq!(s.try_from_index(25), None);
+        assert_eq!(s.try_from_index(usize::MAX), None);
+        // the unchecked one returns a coordinate out of the shape.
+        assert_eq!(s.to_index(s.from_index(25)), None);
+        let s = hexgraph_evenq55.shape();
+        assert_eq!(s.try_from_index(24), Some(s.from_index(24)));
+        assert_eq!(s.try_from_index(25), None);
+    }
+
+    #[rstest]
+    fn to_index_loop_ew(hexgraph_oddr55_lew: Hex5x5Lew) {
+        let s = hexgraph_oddr55_lew.shape();
+        for i in 0..hexgraph_oddr55_lew.node_count() {
+            let c = s.from_index(i);
+            for k in [-2, -1, 1, 3] {
+                let wrapped = C::new(c.r() + 5 * k, c.q());
+                assert_eq!(s.normalize(wrapped), Some(c));
+                assert_eq!(s.to_index(wrapped), Some(i));
+            }
+        }
+        assert_eq!(s.to_index(C::new(0, 5)), None);
+        assert_eq!(s.to_index(C::new(0, -1)), None);
+        // east of the east border wraps to the west border.
+        assert_eq!(s.move_coord(C::new(3, 2), AxisDR::E), Ok(C::new(-1, 2)));
+        assert_eq!(s.move_coord(C::new(-1, 2), AxisDR::W), Ok(C::new(3, 2)));
+    }
+
+    #[rstest]
+    fn neighbor_in_dir(hexgraph_oddr55: Hex5x5, hexgraph_oddr55_lew: Hex5x5Lew) {
+        assert_eq!(
+            hexgraph_oddr55_lew.neighbor_in_dir(C::new(3, 2), AxisDR::E),
+            Some((C::new(-1, 2), true))
+        );
+        assert_eq!(
+            hexgraph_oddr55_lew.neighbor_in_dir(C::new(-1, 2), AxisDR::W),
+            Some((C::new(3, 2), true))
+        );
+        assert_eq!(
+            hexgraph_oddr55_lew.neighbor_in_dir(C::new(1, 2), AxisDR::E),
+            Some((C::new(2, 2), false))
+        );
+        assert_eq!(
+            hexgraph_oddr55_lew.neighbor_in_dir(C::new(0, 0), AxisDR::SW),
+            None
+        );
+        for i in 0..hexgraph_oddr55.node_count() {
+            let c = hexgraph_oddr55.from_index(i);
+            for d in 0..6 {
+                let d = AxisDR::from_index(d).unwrap();
+                if let Some((_, wrap)) = hexgraph_oddr55.neighbor_in_dir(c, d) {
+                    assert!(!wrap);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn const_shape_dims() {
+        use crate::hex::{double_coord::ConstDoubleCoordShape, offset_based::ConstHexOffsetShape};
+        use crate::lattice_abstract::shapes::ConstShapeDims;
+        type Axial = ConstHexAxialShape<OddR, (), 5, 3>;
+        type Offset = ConstHexOffsetShape<OddR, (), 4, 6>;
+        type Double = ConstDoubleCoordShape<OddR, (), 2, 7>;
+        const _: usize = Axial::NODE_COUNT;
+        const _: () = assert!(Offset::H == 4 && Offset::V == 6);
+        let array = [0u8; Double::NODE_COUNT];
+        assert_eq!(array.len(), 14);
+        assert_eq!(Axial::NODE_COUNT, Axial::default().node_count());
+        assert_eq!(
+            (Axial::H, Axial::V),
+            (Axial::default().horizontal(), Axial::default().vertical())
+        );
+        assert_eq!(Offset::NODE_COUNT, Offset::default().node_count());
+        assert_eq!(Double::NODE_COUNT, Double::default().node_count());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[rstest]
+    fn par_node_references(hexgraph_oddr55_lew: Hex5x5Lew) {
+        use rayon::prelude::*;
+        let par = hexgraph_oddr55_lew
+            .par_node_references()
+            .collect::<Vec<_>>();
+        assert!(par
+            .iter()
+            .copied()
+            .eq(hexgraph_oddr55_lew.node_references()));
+        assert!(par.iter().all(|(c, n)| c == *n));
+    }
+
+    #[test]
+    fn reachable_within() {
+        type Hex7x7 = HexGraphConst<(), u32, OddR, 7, 7>;
+        let mut graph = Hex7x7::new_with_s(|_| (), |_, _| 1);
+        let center = graph.shape().from_offset(Offset::new(3, 3));
+        let reachable = graph.reachable_within(center, 2, |e| *e);
+        assert_eq!(reachable.len(), 1 + 6 + 12);
+        assert_eq!(reachable[&center], 0);
+        assert!(reachable
+            .iter()
+            .all(|(c, d)| c.distance(&center) == *d as usize));
+        assert_eq!(graph.reachable_within(center, 0, |e| *e).len(), 1);
+        assert!(graph.reachable_within(C::new(9, 9), 2, |e| *e).is_empty());
+
+        // block the edges to the east of the center.
+        let east = graph.neighbor_in_dir(center, AxisDR::E).unwrap().0;
+        *graph.edge_weight_mut_between(center, east).unwrap() = u32::MAX;
+        let reachable = graph.reachable_within(center, 2, |e| *e);
+        // the node 2 steps to the east needs 3 steps to detour.
+        assert_eq!(reachable.len(), 1 + 6 + 12 - 1);
+        assert_eq!(reachable[&east], 2);
+        let reachable = graph.reachable_within(center, 1, |e| *e);
+        assert_eq!(reachable.len(), 1 + 5);
+        assert!(!reachable.contains_key(&east));
+
+        // the start out of the range is normalized in the looping shape.
+        type Loop5x5 = HexGraphConstLoopEW<(), u32, OddR, 5, 5>;
+        let graph = Loop5x5::new_with_s(|_| (), |_, _| 1);
+        let start = C::new(9, 2);
+        let normalized = graph
+            .shape()
+            .from_index(graph.shape().to_index(start).unwrap());
+        assert_ne!(start, normalized);
+        let reachable = graph.reachable_within(start, 1, |e| *e);
+        assert_eq!(reachable.len(), 1 + 6);
+        assert_eq!(reachable[&normalized], 0);
+        for c in reachable.keys() {
+            let i = graph.shape().to_index(*c).unwrap();
+            assert_eq!(graph.shape().from_index(i), *c);
+        }
+    }
+
+    #[rstest]
+    fn edge_weight_between(hexgraph_oddr55: Hex5x5, hexgraph_oddr55_lew: Hex5x5Lew) {
+        fn check<S>(graph: &LatticeGraph<C, (C, AxisR), S>)
+        where
+            S: Shape<Coordinate = C, Axis = AxisR>,
+        {
+            for i in 0..graph.node_count() {
+                let a = graph.from_index(i);
+                for b in graph.neighbors(a) {
+                    let w = graph.edge_weight_between(a, b);
+                    assert!(w.is_some());
+                    assert_eq!(w, graph.edge_weight_between(b, a));
+                    assert_eq!(w, graph.edge_weight(*w.unwrap()));
+                }
+            }
+        }
+        check(&hexgraph_oddr55);
+        check(&hexgraph_oddr55_lew);
+        let graph = hexgraph_oddr55;
+        assert_eq!(
+            graph.edge_weight_between(C::new(1, 2), C::new(2, 2)),
+            Some(&(C::new(1, 2), AxisR::E))
+        );
+        assert_eq!(
+            graph.edge_weight_between(C::new(2, 2), C::new(1, 2)),
+            Some(&(C::new(1, 2), AxisR::E))
+        );
+        assert_eq!(graph.edge_weight_between(C::new(1, 2), C::new(3, 2)), None);
+        assert_eq!(graph.edge_weight_between(C::new(1, 2), C::new(1, 2)), None);
+        // wraps around the loop.
+        assert_eq!(
+            hexgraph_oddr55_lew.edge_weight_between(C::new(-1, 2), C::new(3, 2)),
+            Some(&(C::new(3, 2), AxisR::E))
+        );
+    }
+
+    #[rstest]
+    fn edge_weight_mut_between(mut hexgraph_oddr55: Hex5x5, mut hexgraph_oddr55_lew: Hex5x5Lew) {
+        let graph = &mut hexgraph_oddr55;
+        let (a, b) = (C::new(2, 2), C::new(1, 2));
+        *graph.edge_weight_mut_between(a, b).unwrap() = (C::new(9, 9), AxisR::NE);
+        assert_eq!(
+            graph.edge_weight((C::new(1, 2), AxisR::E)),
+            Some(&(C::new(9, 9), AxisR::NE))
+        );
+        assert_eq!(
+            graph.edge_weight_between(b, a),
+            Some(&(C::new(9, 9), AxisR::NE))
+        );
+        // the other edges are not changed.
+        assert_eq!(
+            graph.edge_weight_between(a, C::new(3, 2)),
+            Some(&(C::new(2, 2), AxisR::E))
+        );
+        assert_eq!(
+            graph.edge_weight_mut_between(C::new(1, 2), C::new(3, 2)),
+            None
+        );
+        assert_eq!(graph.edge_weight_mut_between(a, a), None);
+        assert_eq!(
+            graph.edge_weight_mut_between(C::new(5, 0), C::new(4, 0)),
+            None
+        );
+        assert_eq!(graph.edge_weight_between(C::new(5, 0), C::new(4, 0)), None);
+
+        let graph = &mut hexgraph_oddr55_lew;
+        *graph
+            .edge_weight_mut_between(C::new(3, 2), C::new(-1, 2))
+            .unwrap() = (C::new(0, 0), AxisR::SE);
+        assert_eq!(
+            graph.edge_weight((C::new(3, 2), AxisR::E)),
+            Some(&(C::new(0, 0), AxisR::SE))
+        );
+    }
+
+    #[rstest]
+    fn for_each(hexgraph_oddr55: Hex5x5, hexgraph_oddr55_lew: Hex5x5Lew) {
+        fn check<S>(graph: &LatticeGraph<C, (C, AxisR), S>)
+        where
+            S: Shape<Coordinate = C, Axis = AxisR>,
+        {
+            for i in 0..graph.node_count() {
+                let c = graph.from_index(i);
+                let mut neighbors = Vec::new();
+                graph.for_each_neighbor(c, |n| neighbors.push(n));
+                assert!(neighbors.into_iter().eq(graph.neighbors(c)));
+                let mut edges = Vec::new();
+                graph.for_each_edge(c, |e| edges.push(e));
+                assert!(edges.into_iter().eq(graph.edges(c)));
+            }
+            graph.for_each_neighbor(C::new(5, 5), |_| unreachable!());
+            graph.for_each_edge(C::new(5, 5), |_| unreachable!());
+        }
+        check(&hexgraph_oddr55);
+        check(&hexgraph_oddr55_lew);
+        check(&hexgraph_oddr55.with_connectivity_cache());
+    }
+
+    #[rstest]
+    fn adjacencies(hexgraph_oddr55: Hex5x5, hexgraph_oddr55_lew: Hex5x5Lew) {
+        fn check<S>(graph: &LatticeGraph<C, (C, AxisR), S>)
+        where
+            S: Shape<Coordinate = C, Axis = AxisR>,
+        {
+            for i in 0..graph.node_count() {
+                let c = graph.from_index(i);
+                let edges = graph.edges(c).collect::<Vec<_>>();
+                assert_eq!(graph.adjacencies(c).count(), edges.len());
+                for ((d, t, w), e) in graph.adjacencies(c).zip(edges) {
+                    assert_eq!((d, t, w), (*e.direction(), e.target(), e.weight()));
+                }
+            }
+            assert_eq!(graph.adjacencies(C::new(5, 5)).count(), 0);
+        }
+        check(&hexgraph_oddr55);
+        check(&hexgraph_oddr55_lew);
+        check(&hexgraph_oddr55.with_connectivity_cache());
+    }
+
+    #[rstest]
+    fn fill(hexgraph_oddr55: Hex5x5, hexgraph_oddr55_lew: Hex5x5Lew) {
+        fn check<S>(mut graph: LatticeGraph<C, (C, AxisR), S>)
+        where
+            S: Shape<Coordinate = C, Axis = AxisR>,
+        {
+            let sentinel = C::new(-100, -100);
+            graph.fill_nodes(sentinel);
+            graph.fill_edges((sentinel, AxisR::NE));
+            for i in 0..graph.node_count() {
+                let c = graph.from_index(i);
+                assert_eq!(graph.node_weight(c), Some(&sentinel));
+                assert!(graph.edges(c).all(|e| e.weight() == &(sentinel, AxisR::NE)));
+            }
+        }
+        check(hexgraph_oddr55);
+        check(hexgraph_oddr55_lew);
+        // drop the filled graph with the edge weights which need drop.
+        let mut graph = HexGraphConst::<(), String, OddR, 5, 5>::new_s();
+        graph.fill_edges("x".to_string());
+        assert!(graph.edge_references().all(|e| e.weight() == "x"));
+    }
+
+    #[rstest]
+    fn border_nodes(hexgraph_oddr55: Hex5x5, hexgraph_oddr55_lew: Hex5x5Lew) {
+        assert_eq!(hexgraph_oddr55.border_nodes().count(), 16);
+        // only the top and bottom rows are on the border when it loops east and west.
+        assert_eq!(hexgraph_oddr55_lew.border_nodes().count(), 10);
+        assert!(hexgraph_oddr55_lew
+            .border_nodes()
+            .all(|c| c.q() == 0 || c.q() == 4));
+        assert!(!hexgraph_oddr55.is_border(C::new(5, 0)));
+        // the west end of the middle row is on the border only when it doesn't loop.
+        let west = hexgraph_oddr55.shape().from_offset(Offset::new(0, 2));
+        assert!(hexgraph_oddr55.is_border(west));
+        assert!(!hexgraph_oddr55_lew.is_border(west));
+        assert!(!hexgraph_oddr55_lew.is_border(C::new(0, 5)));
+    }
+
+    #[rstest]
+    fn axis_count(hexgraph_oddr55: Hex5x5, hexgraph_evenq55: Hex5x5EQ) {
+        assert_eq!(hexgraph_oddr55.axis_count(), 3);
+        assert_eq!(hexgraph_oddr55.direction_count(), 6);
+        assert_eq!(hexgraph_oddr55.axis_name(1), Some("E"));
+        assert_eq!(hexgraph_oddr55.axis_name(3), None);
+        assert_eq!(hexgraph_evenq55.axis_name(0), Some(AxisQ::N.name()));
+        let di = DiHexGraphConst::<(), (), OddR, (), 5, 5>::new_with_s(|_| (), |_, _| ());
+        assert_eq!(di.axis_count(), 6);
+        assert_eq!(di.direction_count(), 6);
+        assert_eq!(di.axis_name(5), Some("NW"));
+    }
+
+    #[test]
+    fn out_edges() {
+        type DiHex = DiHexGraphConst<(), (C, AxisDR), OddR, (), 5, 3>;
+        let graph = DiHex::new_with_s(|_| (), |c, d| (c, d));
+        for i in 0..graph.node_count() {
+            let c = graph.from_index(i);
+            let out = graph.out_edges(c).collect::<Vec<_>>();
+            assert!(out.iter().all(|(d, e)| **e == (c, *d)));
+            assert!(out
+                .iter()
+                .map(|(_, e)| **e)
+                .eq(graph.edges_directed(c, Outgoing).map(|e| *e.weight())));
+            assert!(out.windows(2).all(|w| w[0].0 < w[1].0));
+        }
+        let out = graph
+            .out_edges(C::new(0, 0))
+            .map(|(d, _)| d)
+            .collect::<Vec<_>>();
+        assert_eq!(out, vec![AxisDR::NE, AxisDR::E]);
+        assert_eq!(graph.out_edges(C::new(5, 0)).count(), 0);
+    }
+
+    #[rstest]
+    fn neighbors_oriented(hexgraph_oddr55: Hex5x5) {
+        let c = C::new(1, 2);
+        let expected = [
+            C::new(1, 3),
+            C::new(2, 2),
+            C::new(2, 1),
+            C::new(1, 1),
+            C::new(0, 2),
+            C::new(0, 3),
+        ];
+        assert!(hexgraph_oddr55
+            .neighbors_oriented(c)
+            .eq(IntoIterator::into_iter(expected)));
+        assert!(hexgraph_oddr55
+            .neighbors_oriented(c)
+            .eq(hexgraph_oddr55.neighbors(c)));
+        assert!(hexgraph_oddr55
+            .neighbors_oriented(C::new(0, 0))
+            .eq(IntoIterator::into_iter([C::new(0, 1), C::new(1, 0)])));
+        assert_eq!(hexgraph_oddr55.neighbors_oriented(C::new(-3, 0)).count(), 0);
+    }
+
+    #[test]
+    fn offset_roundtrip_flat_top() {
+        fn check<B>()
+        where
+            HexAxialShape<B, (), usize, usize>: Shape<Coordinate = C>,
+        {
+            let graph = HexGraph::<(), (), B>::new(HexAxialShape::new(5, 5));
+            let s = graph.shape();
+            for h in 0..5 {
+                for v in 0..5 {
+                    let o = Offset::new(h, v);
+                    let c = s.from_offset(o);
+                    assert_eq!(s.to_offset(c).ok(), Some(o));
+                    assert_eq!(s.from_offset(s.to_offset(c).unwrap()), c);
+                }
+            }
+        }
+        check::<OddQ>();
+        check::<EvenQ>();
+        // (2, -1) is in the bottom of the third column in the diagram of OddQ.
+        let s = HexAxialShape::<OddQ, (), usize, usize>::new(5, 5);
+        assert_eq!(s.to_offset(C::new(2, -1)), Ok(Offset::new(2, 0)));
+        assert_eq!(s.from_offset(Offset::new(1, 1)), C::new(1, 1));
+    }
+
+    #[test]
+    fn loop_ew_flat_top() {
+        let s = HexAxialShape::<EvenQ, LoopEW, usize, usize>::new(4, 3);
+        for i in 0..s.node_count() {
+            let c = s.from_index(i);
+            assert_eq!(s.to_index(c), Some(i));
+            for d in 0..6 {
+                let d = AxisDQ::from_index(d).unwrap();
+                if let Ok(t) = s.move_coord(c, d) {
+                    assert!(s.to_offset(t).is_ok());
+                    assert_eq!(s.move_coord(t, d.backward()), Ok(c));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn loop_ns() {
+        type Hex5x5EQns = HexGraphConstLoopNS<C, (C, AxisQ), EvenQ, 5, 5>;
+        let graph = Hex5x5EQns::new_with_s(|x| x, |n, d| (n, d));
+        let s = graph.shape();
+        let offsets = |c: C| {
+            graph
+                .neighbors(c)
+                .map(|n| {
+                    let o = s.to_offset(n).unwrap();
+                    (o.horizontal(), o.vertical())
+                })
+                .collect::<Vec<_>>()
+        };
+        let o = |h, v| s.from_offset(Offset::new(h, v));
+        // bottom border
+        assert_eq!(
+            offsets(o(2, 0)),
+            [(2, 1), (3, 1), (3, 0), (2, 4), (1, 0), (1, 1)]
+        );
+        assert_eq!(
+            offsets(o(1, 0)),
+            [(1, 1), (2, 0), (2, 4), (1, 4), (0, 4), (0, 0)]
+        );
+        // top border
+        assert_eq!(
+            offsets(o(2, 4)),
+            [(2, 0), (3, 0), (3, 4), (2, 3), (1, 4), (1, 0)]
+        );
+        assert_eq!(
+            offsets(o(1, 4)),
+            [(1, 0), (2, 4), (2, 3), (1, 3), (0, 3), (0, 4)]
+        );
+        // east-west doesn't loop.
+        assert_eq!(offsets(o(0, 4)), [(0, 0), (1, 0), (1, 4), (0, 3)]);
+        assert_eq!(s.normalize(C::new(2, 4)), Some(C::new(2, -1)));
+        assert_eq!(s.to_index(C::new(2, 4)), s.to_index(C::new(2, -1)));
+        assert_eq!(s.normalize(C::new(5, 0)), None);
+
+        // point-top needs the even vertical size.
+        let s = HexAxialShape::<OddR, LoopNS, usize, usize>::new(3, 4);
+        for i in 0..s.node_count() {
+            let c = s.from_index(i);
+            assert_eq!(s.to_index(c), Some(i));
+            for d in 0..6 {
+                let d = AxisDR::from_index(d).unwrap();
+                if let Ok(t) = s.move_coord(c, d) {
+                    assert!(s.to_offset(t).is_ok());
+                    assert_eq!(s.move_coord(t, d.backward()), Ok(c));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn partially_directed() {
+        type OneWay = HexGraphConst<(), (), PartiallyDirected<OddR, 0b001>, 5, 5>;
+        let graph = OneWay::new_s();
+        let c = C::new(1, 2);
+        assert!(graph.neighbors(c).eq(IntoIterator::into_iter([
+            C::new(1, 3),
+            C::new(2, 2),
+            C::new(2, 1),
+            C::new(0, 2),
+            C::new(0, 3),
+        ])));
+        // NE is one-way, so (1, 1) -> (1, 2) but not (1, 2) -> (1, 1).
+        assert!(graph.neighbors(C::new(1, 1)).any(|n| n == c));
+        assert!(!graph.neighbors(c).any(|n| n == C::new(1, 1)));
+        assert!(graph.shape().move_coord(c, AxisDR::SW).is_err());
+        // E and SE remain bidirectional.
+        assert!(graph.neighbors(C::new(2, 2)).any(|n| n == c));
+        assert!(graph.neighbors(C::new(2, 1)).any(|n| n == c));
+        // the graph is directed, with the edges to both directions except for NE.
+        assert!(graph.is_directed());
+        let undirected = hexgraph_oddr55();
+        let ne = undirected
+            .edge_references()
+            .filter(|e| e.weight().1 == AxisR::NE)
+            .count();
+        assert_eq!(
+            graph.edge_references().count(),
+            undirected.edge_references().count() * 2 - ne
+        );
+        assert!(graph
+            .edge_references()
+            .all(|e| graph.neighbors(e.source()).any(|n| n == e.target())));
+        let incoming = graph
+            .edges_directed(c, petgraph::Incoming)
+            .map(|e| e.source())
+            .collect::<Vec<_>>();
+        assert_eq!(incoming.len(), 5);
+        assert!(incoming.contains(&C::new(1, 1)));
+        assert!(!incoming.contains(&C::new(1, 3)));
+        for n in graph.node_identifiers() {
+            let mut incoming = graph
+                .neighbors_directed(n, petgraph::Incoming)
+                .collect::<Vec<_>>();
+            let mut sources = graph
+                .edge_references()
+                .filter(|e| e.target() == n)
+                .map(|e| e.source())
+                .collect::<Vec<_>>();
+            incoming.sort();
+            sources.sort();
+            assert_eq!(incoming, sources);
+        }
+    }
 }
@@ -3,8 +3,8 @@ use const_generic_wrap::WrapUSIZE;
 use std::marker::PhantomData;
 
 use crate::{
-    hex::shapes::*,
-    lattice_abstract::{Axis, Coordinate, Offset, Shape},
+    hex::{offset_based::HexOffset, shapes::*},
+    lattice_abstract::{shapes::reverse_direction, Axis, Coordinate, Offset, Shape},
 };
 
 pub trait AxialCoord<I = isize>: Clone + Coordinate {
@@ -18,6 +18,7 @@ pub trait AxialCoord<I = isize>: Clone + Coordinate {
 
 /// Axial based coordinates for hex graph.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HexAxial {
     pub(crate) r: isize,
     pub(crate) q: isize,
@@ -40,6 +41,142 @@ impl HexAxial {
     pub fn new(r: isize, q: isize) -> Self {
         Self { q, r }
     }
+
+    /// Returns the distance to `other` in the hex grid, the count of the steps to move.
+    /// It doesn't depend on the layout of the graph.
+    pub fn distance(&self, other: &HexAxial) -> usize {
+        let dr = self.r - other.r;
+        let dq = self.q - other.q;
+        ((dr.abs() + (dr + dq).abs() + dq.abs()) / 2) as usize
+    }
+
+    /// Rotates the coordinate 60 degrees clockwise around the origin,
+    /// that is, the direction of the index `i` of [`AxisDR`] (or [`AxisDQ`]) to the index `i + 1`.
+    /// It doesn't depend on the layout of the graph.
+    pub fn rotate_cw(&self) -> HexAxial {
+        HexAxial::new(self.r + self.q, -self.r)
+    }
+
+    /// Rotates the coordinate 60 degrees counterclockwise around the origin. The inverse of [`rotate_cw`](`Self::rotate_cw`).
+    pub fn rotate_ccw(&self) -> HexAxial {
+        HexAxial::new(-self.q, self.r + self.q)
+    }
+
+    /// Reflects the coordinate across the axis where `q` is constant,
+    /// swapping `r` and `s` of the cube coordinate (`s = -r - q`).
+    pub fn reflect_q(&self) -> HexAxial {
+        HexAxial::new(-self.r - self.q, self.q)
+    }
+
+    /// Reflects the coordinate across the axis where `r` is constant,
+    /// swapping `q` and `s` of the cube coordinate (`s = -r - q`).
+    pub fn reflect_r(&self) -> HexAxial {
+        HexAxial::new(self.r, -self.r - self.q)
+    }
+
+    /// Reflects the coordinate across the axis where `s = -r - q` is constant, swapping `r` and `q`.
+    pub fn reflect_s(&self) -> HexAxial {
+        HexAxial::new(self.q, self.r)
+    }
+
+    /// Iterate the coordinates in hex spiral order, the `center` first and then each ring up to `max_radius`.
+    /// This doesn't check whether the coordinate is inside of a graph.
+    pub fn spiral_coords(center: HexAxial, max_radius: usize) -> impl Iterator<Item = HexAxial> {
+        axial_spiral(center, max_radius)
+    }
+
+    /// Iterate the coordinates on the straight line from `a` to `b`, both ends included.
+    /// It yields `a.distance(&b) + 1` coordinates, each is the neighbor of the previous one.
+    /// This doesn't check whether the coordinate is inside of a graph.
+    pub fn line_coords(a: HexAxial, b: HexAxial) -> impl Iterator<Item = HexAxial> {
+        axial_line(a, b)
+    }
+
+    /// Converts from the offset coordinate of the layout `B`, such as [`OddR`] or [`EvenQ`].
+    /// It is same as [`from_offset`](`Shape::from_offset`) of [`HexAxialShape`] with `B`.
+    pub fn from_hex_offset<B: OE + RQ>(offset: HexOffset) -> Self {
+        offset_to_axial::<B, _>(Offset::new(offset.horizontal(), offset.vertical()))
+    }
+
+    /// Converts to the offset coordinate of the layout `B`, such as [`OddR`] or [`EvenQ`].
+    /// Returns [`None`] if the offset is negative.
+    pub fn to_hex_offset<B: OE + RQ>(&self) -> Option<HexOffset> {
+        let c = B::CONVERT_OFFSET as isize;
+        let (h, v) = if B::IS_FLAT_TOP {
+            (self.r, self.q + (self.r + c).div_euclid(2))
+        } else {
+            (self.r + (self.q + c).div_euclid(2), self.q)
+        };
+        if h < 0 || v < 0 {
+            None
+        } else {
+            Some(HexOffset::new(h as usize, v as usize))
+        }
+    }
+}
+
+/// Unit vectors of `(r, q)` in the order of the index of [`AxisDR`] (or [`AxisDQ`]).
+const AXIAL_DIRECTIONS: [(isize, isize); 6] = [(0, 1), (1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1)];
+
+/// Iterate the coordinates which is `radius` away from `center`.
+/// Starts from the `W` (or `SW`) corner and goes clockwise.
+pub(crate) fn axial_ring<HA: AxialCoord>(center: HA, radius: usize) -> impl Iterator<Item = HA> {
+    let r = radius as isize;
+    let (cr, cq) = (center.r(), center.q());
+    let sides = if radius == 0 { 0 } else { 6 };
+    core::iter::once(center)
+        .take(if radius == 0 { 1 } else { 0 })
+        .chain((0..sides).flat_map(move |i| {
+            let (sr, sq) = AXIAL_DIRECTIONS[(i + 4) % 6];
+            let (dr, dq) = AXIAL_DIRECTIONS[i];
+            (0..r).map(move |j| HA::new(cr + sr * r + dr * j, cq + sq * r + dq * j))
+        }))
+}
+
+/// Converts the offset to the axial coordinate in the layout `B`.
+fn offset_to_axial<B: OE + RQ, HA: AxialCoord>(offset: Offset) -> HA {
+    if B::IS_FLAT_TOP {
+        HA::new(
+            offset.horizontal() as isize,
+            offset.vertical() as isize - ((offset.horizontal() + B::CONVERT_OFFSET) / 2) as isize,
+        )
+    } else {
+        HA::new(
+            offset.horizontal() as isize - ((offset.vertical() + B::CONVERT_OFFSET) / 2) as isize,
+            offset.vertical() as isize,
+        )
+    }
+}
+
+/// Iterate the coordinates on the line with the linear interpolation on the cube coordinates.
+/// See [`HexAxial::line_coords`].
+pub(crate) fn axial_line<HA: AxialCoord>(a: HA, b: HA) -> impl Iterator<Item = HA> {
+    let (ar, aq) = (a.r(), a.q());
+    let (dr, dq) = (b.r() - ar, b.q() - aq);
+    let n = (dr.abs() + dq.abs() + (dr + dq).abs()) / 2;
+    (0..=n).map(move |i| {
+        let t = if n == 0 { 0. } else { i as f64 / n as f64 };
+        // nudge to break the ties in the same side.
+        let r = ar as f64 + dr as f64 * t + 1e-6;
+        let q = aq as f64 + dq as f64 * t + 2e-6;
+        let s = -r - q;
+        let (mut rr, mut rq, rs) = (r.round(), q.round(), s.round());
+        let (er, eq, es) = ((rr - r).abs(), (rq - q).abs(), (rs - s).abs());
+        if er > eq && er > es {
+            rr = -rq - rs;
+        } else if eq > es {
+            rq = -rr - rs;
+        }
+        HA::new(rr as isize, rq as isize)
+    })
+}
+
+/// Iterate the coordinates in hex spiral order. See [`HexAxial::spiral_coords`].
+pub(crate) fn axial_spiral<HA: AxialCoord>(
+    center: HA,
+    max_radius: usize,
+) -> impl Iterator<Item = HA> {
+    (0..=max_radius).flat_map(move |r| axial_ring(center, r))
 }
 
 impl Coordinate for HexAxial {}
@@ -64,6 +201,11 @@ impl AxialCoord for HexAxial {
 pub trait HexAxialShapeBase<HA: AxialCoord>: OE + RQ + Clone {
     type Axis: Axis;
     unsafe fn move_coord_unchecked(coord: HA, dir: <Self::Axis as Axis>::Direction) -> HA;
+    /// Whether it can move to the direction. It is used to make some axes one-way.
+    #[inline]
+    fn can_move(_dir: &<Self::Axis as Axis>::Direction) -> bool {
+        true
+    }
 }
 
 impl<HA: AxialCoord> HexAxialShapeBase<HA> for OddR {
@@ -109,6 +251,28 @@ where
     unsafe fn move_coord_unchecked(coord: HA, dir: A::Direction) -> HA {
         T::move_coord_unchecked(coord, dir)
     }
+
+    fn can_move(dir: &A::Direction) -> bool {
+        T::can_move(dir)
+    }
+}
+
+impl<T, A, HA: AxialCoord, const MASK: u8> HexAxialShapeBase<HA> for PartiallyDirected<T, MASK>
+where
+    T: HexAxialShapeBase<HA, Axis = A>,
+    A: Axis,
+    A::Direction: Axis<Direction = A::Direction>,
+{
+    type Axis = A::Direction;
+
+    unsafe fn move_coord_unchecked(coord: HA, dir: A::Direction) -> HA {
+        T::move_coord_unchecked(coord, dir)
+    }
+
+    fn can_move(dir: &A::Direction) -> bool {
+        (A::is_forward_direction(dir) || MASK & (1 << A::from_direction(*dir).to_index()) == 0)
+            && T::can_move(dir)
+    }
 }
 
 fn move_coord_r<HA: AxialCoord>(coord: HA, dir: AxisDR) -> HA {
@@ -171,6 +335,43 @@ impl<ShapeBase, Loop, H, V, HA> HexAxialShape<ShapeBase, Loop, H, V, HA> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<ShapeBase, Loop, H, V, HA> serde::Serialize for HexAxialShape<ShapeBase, Loop, H, V, HA>
+where
+    H: Clone + Into<usize>,
+    V: Clone + Into<usize>,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut st = serializer.serialize_struct("HexAxialShape", 2)?;
+        st.serialize_field("h", &self.h.clone().into())?;
+        st.serialize_field("v", &self.v.clone().into())?;
+        st.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, ShapeBase, Loop, H, V, HA> serde::Deserialize<'de>
+    for HexAxialShape<ShapeBase, Loop, H, V, HA>
+where
+    H: core::convert::TryFrom<usize>,
+    V: core::convert::TryFrom<usize>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "HexAxialShape")]
+        struct Size {
+            h: usize,
+            v: usize,
+        }
+        let Size { h, v } = Size::deserialize(deserializer)?;
+        let h = H::try_from(h).map_err(|_| D::Error::custom("invalid horizontal size"))?;
+        let v = V::try_from(v).map_err(|_| D::Error::custom("invalid vertical size"))?;
+        Ok(Self::new(h, v))
+    }
+}
+
 /// Shape for Axial based coordinates with const size. This is ZST.
 #[cfg(feature = "const-generic-wrap")]
 pub type ConstHexAxialShape<T, L, const H: usize, const V: usize> =
@@ -183,6 +384,16 @@ impl<T, L, const H: usize, const V: usize> Default for ConstHexAxialShape<T, L,
     }
 }
 
+#[cfg(feature = "const-generic-wrap")]
+impl<T, L, HA, const H: usize, const V: usize> crate::lattice_abstract::shapes::ConstShapeDims
+    for HexAxialShape<T, L, WrapUSIZE<H>, WrapUSIZE<V>, HA>
+where
+    Self: Shape,
+{
+    const H: usize = H;
+    const V: usize = V;
+}
+
 impl<B, H, V, HA> Shape for HexAxialShape<B, (), H, V, HA>
 where
     HA: AxialCoord,
@@ -234,19 +445,7 @@ where
     }
 
     fn from_offset(&self, offset: crate::lattice_abstract::Offset) -> Self::Coordinate {
-        if B::IS_FLAT_TOP {
-            HA::new(
-                offset.horizontal() as isize,
-                offset.vertical() as isize
-                    - ((offset.horizontal() + B::CONVERT_OFFSET) / 2) as isize,
-            )
-        } else {
-            HA::new(
-                offset.horizontal() as isize
-                    - ((offset.vertical() + B::CONVERT_OFFSET) / 2) as isize,
-                offset.vertical() as isize,
-            )
-        }
+        offset_to_axial::<B, HA>(offset)
     }
 
     fn horizontal_edge_size(&self, _axis: Self::Axis) -> usize {
@@ -262,6 +461,9 @@ where
         coord: Self::Coordinate,
         dir: <Self::Axis as Axis>::Direction,
     ) -> Result<Self::Coordinate, Self::CoordinateMoveError> {
+        if !B::can_move(&dir) {
+            return Err(());
+        }
         let c = unsafe { B::move_coord_unchecked(coord, dir) };
         if self.to_offset(c).is_ok() {
             Ok(c)
@@ -270,6 +472,22 @@ where
         }
     }
 
+    fn move_coord_reverse(
+        &self,
+        coord: Self::Coordinate,
+        dir: <Self::Axis as Axis>::Direction,
+    ) -> Result<Self::Coordinate, Self::CoordinateMoveError> {
+        if !B::can_move(&dir) {
+            return Err(());
+        }
+        let c = unsafe { B::move_coord_unchecked(coord, reverse_direction::<B::Axis>(dir)) };
+        if self.to_offset(c).is_ok() {
+            Ok(c)
+        } else {
+            Err(())
+        }
+    }
+
     unsafe fn move_coord_unchecked(
         &self,
         coord: Self::Coordinate,
@@ -327,26 +545,179 @@ where
         coord: Self::Coordinate,
         dir: <Self::Axis as Axis>::Direction,
     ) -> Result<Self::Coordinate, Self::CoordinateMoveError> {
+        if !B::can_move(&dir) {
+            return Err(());
+        }
         let c = unsafe { B::move_coord_unchecked(coord, dir) };
-        let q = c.q();
-        if (q as usize) >= self.vertical() {
+        self.normalize(c).ok_or(())
+    }
+
+    fn move_coord_reverse(
+        &self,
+        coord: Self::Coordinate,
+        dir: <Self::Axis as Axis>::Direction,
+    ) -> Result<Self::Coordinate, Self::CoordinateMoveError> {
+        if !B::can_move(&dir) {
             return Err(());
         }
+        let c = unsafe { B::move_coord_unchecked(coord, reverse_direction::<B::Axis>(dir)) };
+        self.normalize(c).ok_or(())
+    }
+
+    unsafe fn move_coord_unchecked(
+        &self,
+        coord: Self::Coordinate,
+        dir: <Self::Axis as Axis>::Direction,
+    ) -> Self::Coordinate {
+        self.normalize(B::move_coord_unchecked(coord, dir))
+            .unwrap_or_else(|| crate::unreachable_debug_checked())
+    }
+
+    fn to_index(&self, coord: Self::Coordinate) -> Option<usize> {
+        let offset = self.to_offset(self.normalize(coord)?);
+        offset.ok().map(|o| self.offset_to_index(o))
+    }
+}
+
+impl<B, H, V, HA> HexAxialShape<B, LoopEW, H, V, HA>
+where
+    HA: AxialCoord,
+    B: HexAxialShapeBase<HA>,
+    H: Clone + Into<usize>,
+    V: Clone + Into<usize>,
+{
+    /// Wrap the coordinate in east-west direction into the graph.
+    /// Returns [`None`] if it is out of the graph in north-south direction.
+    pub fn normalize(&self, coord: HA) -> Option<HA> {
+        if B::IS_FLAT_TOP {
+            let offset = B::CONVERT_OFFSET as isize;
+            let v = coord.q() + (coord.r() + offset).div_euclid(2);
+            if (v as usize) >= self.vertical() {
+                return None;
+            }
+            let r = coord.r().rem_euclid(self.horizontal() as isize);
+            return Some(HA::new(r, v - (r + offset) / 2));
+        }
+        let q = coord.q();
+        if (q as usize) >= self.vertical() {
+            return None;
+        }
         let min = -((q + B::CONVERT_OFFSET as isize) / 2);
         let h = self.horizontal() as isize;
-        let mut r = c.r();
-        if r < min {
-            while {
-                r += h;
-                r < min
-            } {}
-        } else {
-            let max = h - min;
-            while r >= max {
-                r -= h;
-            }
+        let r = (coord.r() - min).rem_euclid(h) + min;
+        Some(HA::new(r, q))
+    }
+}
+
+impl<B, H, V, HA> Shape for HexAxialShape<B, LoopNS, H, V, HA>
+where
+    HA: AxialCoord,
+    B: HexAxialShapeBase<HA>,
+    H: Clone + Into<usize>,
+    V: Clone + Into<usize>,
+{
+    type Axis = B::Axis;
+    type Coordinate = HA;
+    type OffsetConvertError = ();
+    type CoordinateMoveError = ();
+
+    fn horizontal(&self) -> usize {
+        self.h.clone().into()
+    }
+
+    fn vertical(&self) -> usize {
+        self.v.clone().into()
+    }
+
+    #[inline]
+    fn to_offset(&self, coord: Self::Coordinate) -> Result<Offset, Self::OffsetConvertError> {
+        self.convert::<()>().to_offset(coord)
+    }
+
+    #[inline]
+    unsafe fn to_offset_unchecked(&self, coord: Self::Coordinate) -> Offset {
+        self.convert::<()>().to_offset_unchecked(coord)
+    }
+
+    #[inline]
+    fn from_offset(&self, offset: crate::lattice_abstract::Offset) -> Self::Coordinate {
+        self.convert::<()>().from_offset(offset)
+    }
+
+    fn horizontal_edge_size(&self, _axis: Self::Axis) -> usize {
+        self.horizontal()
+    }
+
+    fn vertical_edge_size(&self, _axis: Self::Axis) -> usize {
+        self.vertical()
+    }
+
+    fn move_coord(
+        &self,
+        coord: Self::Coordinate,
+        dir: <Self::Axis as Axis>::Direction,
+    ) -> Result<Self::Coordinate, Self::CoordinateMoveError> {
+        if !B::can_move(&dir) {
+            return Err(());
+        }
+        let c = unsafe { B::move_coord_unchecked(coord, dir) };
+        self.normalize(c).ok_or(())
+    }
+
+    fn move_coord_reverse(
+        &self,
+        coord: Self::Coordinate,
+        dir: <Self::Axis as Axis>::Direction,
+    ) -> Result<Self::Coordinate, Self::CoordinateMoveError> {
+        if !B::can_move(&dir) {
+            return Err(());
         }
+        let c = unsafe { B::move_coord_unchecked(coord, reverse_direction::<B::Axis>(dir)) };
+        self.normalize(c).ok_or(())
+    }
 
-        Ok(HA::new(r, q))
+    unsafe fn move_coord_unchecked(
+        &self,
+        coord: Self::Coordinate,
+        dir: <Self::Axis as Axis>::Direction,
+    ) -> Self::Coordinate {
+        self.normalize(B::move_coord_unchecked(coord, dir))
+            .unwrap_or_else(|| crate::unreachable_debug_checked())
+    }
+
+    fn to_index(&self, coord: Self::Coordinate) -> Option<usize> {
+        let offset = self.to_offset(self.normalize(coord)?);
+        offset.ok().map(|o| self.offset_to_index(o))
+    }
+}
+
+impl<B, H, V, HA> HexAxialShape<B, LoopNS, H, V, HA>
+where
+    HA: AxialCoord,
+    B: HexAxialShapeBase<HA>,
+    H: Clone + Into<usize>,
+    V: Clone + Into<usize>,
+{
+    /// Wrap the coordinate in north-south direction into the graph, keeping the horizontal offset.
+    /// Returns [`None`] if it is out of the graph in east-west direction.
+    /// See [`LoopNS`] for the constraint of the vertical size.
+    pub fn normalize(&self, coord: HA) -> Option<HA> {
+        let offset = B::CONVERT_OFFSET as isize;
+        let v = self.vertical() as isize;
+        if B::IS_FLAT_TOP {
+            let r = coord.r();
+            if (r as usize) >= self.horizontal() {
+                return None;
+            }
+            let shift = (r + offset) / 2;
+            let q = (coord.q() + shift).rem_euclid(v) - shift;
+            return Some(HA::new(r, q));
+        }
+        let h = coord.r() + (coord.q() + offset).div_euclid(2);
+        if (h as usize) >= self.horizontal() {
+            return None;
+        }
+        let q = coord.q().rem_euclid(v);
+        Some(HA::new(h - (q + offset) / 2, q))
     }
 }
@@ -1,8 +1,8 @@
-use crate::hex::shapes::*;
+use crate::hex::{axial_based::HexAxial, offset_based::HexOffset, shapes::*};
 use crate::lattice_abstract::shapes::*;
 #[cfg(feature = "const-generic-wrap")]
 use const_generic_wrap::WrapUSIZE;
-use std::marker::PhantomData;
+use std::{convert::TryFrom, marker::PhantomData};
 
 /// Double coordinate based coordinates for hex graph.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -30,6 +30,44 @@ impl DoubleCoord {
 
 impl Coordinate for DoubleCoord {}
 
+/// The offset of [`OddR`] layout, which is used in [`DoubleCoordShape`].
+impl From<HexOffset> for DoubleCoord {
+    fn from(offset: HexOffset) -> Self {
+        let v = offset.vertical();
+        DoubleCoord::new(offset.horizontal() * 2 + (v & 1), v)
+    }
+}
+
+/// The offset of [`OddR`] layout, which is used in [`DoubleCoordShape`].
+impl From<DoubleCoord> for HexOffset {
+    fn from(coord: DoubleCoord) -> Self {
+        HexOffset::new(coord.h / 2, coord.v)
+    }
+}
+
+impl From<DoubleCoord> for HexAxial {
+    fn from(coord: DoubleCoord) -> Self {
+        HexAxial::new(
+            (coord.h as isize - coord.v as isize).div_euclid(2),
+            coord.v as isize,
+        )
+    }
+}
+
+/// Fails if the double coordinate is negative.
+impl TryFrom<HexAxial> for DoubleCoord {
+    type Error = ();
+
+    fn try_from(coord: HexAxial) -> Result<Self, ()> {
+        let h = coord.r() * 2 + coord.q();
+        if h < 0 || coord.q() < 0 {
+            Err(())
+        } else {
+            Ok(DoubleCoord::new(h as usize, coord.q() as usize))
+        }
+    }
+}
+
 /// A trait to make a type in [`crate::hex::shapes`] to be a shape for double coord.
 pub trait DoubleCoordShapeBase: OE + RQ + Clone {
     type Axis: Axis;
@@ -254,6 +292,16 @@ impl<T: DoubleCoordShapeBase, L, const H: usize, const V: usize> Default
     }
 }
 
+#[cfg(feature = "const-generic-wrap")]
+impl<T, L, A, const H: usize, const V: usize> ConstShapeDims
+    for DoubleCoordShape<T, L, WrapUSIZE<H>, WrapUSIZE<V>, A>
+where
+    Self: Shape,
+{
+    const H: usize = H;
+    const V: usize = V;
+}
+
 impl<B, H, V> Shape for DoubleCoordShape<B, (), H, V, AxisR>
 where
     B: DoubleCoordShapeBase<Axis = AxisR>,
@@ -293,9 +341,7 @@ where
     }
 
     fn from_offset(&self, offset: Offset) -> Self::Coordinate {
-        let v = offset.vertical;
-        let h = offset.horizontal * 2 + (v & 1);
-        DoubleCoord::new(h, v)
+        HexOffset::new(offset.horizontal, offset.vertical).into()
     }
 
     fn move_coord(
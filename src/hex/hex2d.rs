@@ -114,7 +114,10 @@ impl From<AxisDQ> for hex2d::Direction {
 mod tests {
     type C = hex2d::Coordinate;
     use super::*;
-    use crate::hex::shapes::{AxisR, OddR};
+    use crate::{
+        hex::shapes::{AxisR, OddR},
+        lattice_abstract::{shapes::Shape, Offset},
+    };
     use petgraph::{data::DataMap, visit::*};
     use rstest::*;
     use std::array::IntoIter;
@@ -166,4 +169,45 @@ mod tests {
             assert_eq!(neighbor.distance(target), 1);
         }
     }
+
+    #[rstest]
+    #[case(C::new(0, 0), vec![C::new(0, 1), C::new(1, 0)])]
+    #[case(C::new(4, 0), vec![C::new(4, 1), C::new(3, 0), C::new(3, 1)])]
+    #[case(C::new(1, 2), vec![
+        C::new(1, 3),
+        C::new(2, 2),
+        C::new(2, 1),
+        C::new(1, 1),
+        C::new(0, 2),
+        C::new(0, 3),
+    ])]
+    fn neighbors_oddr_shape(#[case] target: C, #[case] neighbors: Vec<C>) {
+        let shape = HexShapeConst::<OddR, (), i32, 5, 5>::default();
+        let e = hex2d::Direction::all()
+            .iter()
+            .filter_map(|d| shape.move_coord(target, AxisDR::from(*d)).ok())
+            .collect::<Vec<_>>();
+        assert_eq!(e.len(), neighbors.len());
+        for n in neighbors {
+            assert!(e.contains(&n));
+        }
+    }
+
+    #[test]
+    fn offset_negative() {
+        let shape = HexShapeConst::<OddR, (), i32, 5, 5>::default();
+        for i in 0..shape.node_count() {
+            let c = shape.from_index(i);
+            assert_eq!(shape.to_index(c), Some(i));
+            assert_eq!(shape.from_offset(shape.to_offset(c).unwrap()), c);
+        }
+        assert!(shape.to_offset(C::new(-1, 0)).is_err());
+        assert!(shape.to_offset(C::new(0, -1)).is_err());
+        assert!(shape.to_offset(C::new(-3, 4)).is_err());
+        assert_eq!(shape.to_offset(C::new(-2, 4)), Ok(Offset::new(0, 4)));
+        assert!(shape.move_coord(C::new(0, 0), AxisDR::W).is_err());
+        assert!(shape.move_coord(C::new(0, 0), AxisDR::SE).is_err());
+        assert!(shape.move_coord(C::new(0, 1), AxisDR::W).is_err());
+        assert_eq!(shape.move_coord(C::new(0, 2), AxisDR::W), Ok(C::new(-1, 2)));
+    }
 }
@@ -107,18 +107,16 @@ fn move_coord_r(
 ) -> Result<HexOffset, ()> {
     let o = coord.0;
     match (dir, o.vertical() & 1 == flag) {
-        (AxisDR::E, _) => o.add_x(1).check_x(horizontal),
+        (AxisDR::E, _) => o.try_add_x(1, horizontal),
         (AxisDR::W, _) => o.sub_x(1),
-        (AxisDR::NE, true) | (AxisDR::NW, false) => o.add_y(1).check_y(vertical),
+        (AxisDR::NE, true) | (AxisDR::NW, false) => o.try_add_y(1, vertical),
         (AxisDR::SE, true) | (AxisDR::SW, false) => o.sub_y(1),
         (AxisDR::NE, false) => o
-            .add_x(1)
-            .check_x(horizontal)
-            .map(|o| o.add_y(1).check_y(vertical))
-            .flatten(),
-        (AxisDR::SE, false) => o.add_x(1).check_x(horizontal).map(|o| o.sub_y(1)).flatten(),
-        (AxisDR::SW, true) => o.sub_x(1).map(|o| o.sub_y(1)).flatten(),
-        (AxisDR::NW, true) => o.sub_x(1).map(|o| o.add_y(1).check_y(vertical)).flatten(),
+            .try_add_x(1, horizontal)
+            .and_then(|o| o.try_add_y(1, vertical)),
+        (AxisDR::SE, false) => o.try_add_x(1, horizontal).and_then(|o| o.sub_y(1)),
+        (AxisDR::SW, true) => o.sub_x(1).and_then(|o| o.sub_y(1)),
+        (AxisDR::NW, true) => o.sub_x(1).and_then(|o| o.try_add_y(1, vertical)),
     }
     .map(|o| HexOffset(o))
     .ok_or(())
@@ -133,19 +131,16 @@ fn move_coord_r_lew(
 ) -> Result<HexOffset, ()> {
     let o = coord.0;
     match (dir, o.vertical() & 1 == flag) {
-        (AxisDR::E, _) => Some(o.add_x(1).check_x(horizontal).unwrap_or_else(|| o.set_x(0))),
+        (AxisDR::E, _) => Some(o.try_add_x(1, horizontal).unwrap_or_else(|| o.set_x(0))),
         (AxisDR::W, _) => Some(o.sub_x(1).unwrap_or_else(|| o.set_x(horizontal - 1))),
-        (AxisDR::NE, true) | (AxisDR::NW, false) => o.add_y(1).check_y(vertical),
+        (AxisDR::NE, true) | (AxisDR::NW, false) => o.try_add_y(1, vertical),
         (AxisDR::SE, true) | (AxisDR::SW, false) => o.sub_y(1),
         (AxisDR::NE, false) => o
-            .add_x(1)
-            .check_x(horizontal)
+            .try_add_x(1, horizontal)
             .unwrap_or_else(|| o.set_x(0))
-            .add_y(1)
-            .check_y(vertical),
+            .try_add_y(1, vertical),
         (AxisDR::SE, false) => o
-            .add_x(1)
-            .check_x(horizontal)
+            .try_add_x(1, horizontal)
             .unwrap_or_else(|| o.set_x(0))
             .sub_y(1),
         (AxisDR::SW, true) => o
@@ -155,8 +150,7 @@ fn move_coord_r_lew(
         (AxisDR::NW, true) => o
             .sub_x(1)
             .unwrap_or_else(|| o.set_x(horizontal - 1))
-            .add_y(1)
-            .check_y(vertical),
+            .try_add_y(1, vertical),
     }
     .map(|o| HexOffset(o))
     .ok_or(())
@@ -237,14 +231,14 @@ fn move_coord_q(
 ) -> Result<HexOffset, ()> {
     let o = coord.0;
     match (dir, o.vertical() & 1 != flag) {
-        (AxisDQ::N, _) => o.add_y(1).check_y(vertical),
+        (AxisDQ::N, _) => o.try_add_y(1, vertical),
         (AxisDQ::S, _) => o.sub_y(1),
-        (AxisDQ::NE, true) | (AxisDQ::NW, false) => o.add_x(1).check_x(horizontal),
+        (AxisDQ::NE, true) | (AxisDQ::NW, false) => o.try_add_x(1, horizontal),
         (AxisDQ::SE, true) | (AxisDQ::SW, false) => o.sub_x(1),
-        (AxisDQ::NE, false) => o.add_y(1).add_x(1).check_x(horizontal),
-        (AxisDQ::SE, false) => o.add_y(1).sub_x(1).map(|o| o.check_y(vertical)).flatten(),
-        (AxisDQ::SW, true) => o.sub_y(1).map(|o| o.sub_x(1)).flatten(),
-        (AxisDQ::NW, true) => o.sub_y(1).map(|o| o.add_x(1).check_x(horizontal)).flatten(),
+        (AxisDQ::NE, false) => o.checked_add_y(1).and_then(|o| o.try_add_x(1, horizontal)),
+        (AxisDQ::SE, false) => o.sub_x(1).and_then(|o| o.try_add_y(1, vertical)),
+        (AxisDQ::SW, true) => o.sub_y(1).and_then(|o| o.sub_x(1)),
+        (AxisDQ::NW, true) => o.sub_y(1).and_then(|o| o.try_add_x(1, horizontal)),
     }
     .map(|o| HexOffset(o))
     .ok_or(())
@@ -259,19 +253,16 @@ fn move_coord_q_lew(
 ) -> Result<HexOffset, ()> {
     let o = coord.0;
     match (dir, o.vertical() & 1 != flag) {
-        (AxisDQ::N, _) => Some(o.add_x(1).check_x(horizontal).unwrap_or_else(|| o.set_x(0))),
+        (AxisDQ::N, _) => Some(o.try_add_x(1, horizontal).unwrap_or_else(|| o.set_x(0))),
         (AxisDQ::S, _) => Some(o.sub_x(1).unwrap_or_else(|| o.set_x(horizontal - 1))),
-        (AxisDQ::NE, true) | (AxisDQ::NW, false) => o.add_x(1).check_x(horizontal),
+        (AxisDQ::NE, true) | (AxisDQ::NW, false) => o.try_add_x(1, horizontal),
         (AxisDQ::SE, true) | (AxisDQ::SW, false) => o.sub_x(1),
         (AxisDQ::NE, false) => o
-            .add_x(1)
-            .check_x(horizontal)
+            .try_add_x(1, horizontal)
             .unwrap_or_else(|| o.set_x(0))
-            .add_y(1)
-            .check_y(vertical),
+            .try_add_y(1, vertical),
         (AxisDQ::SE, false) => o
-            .add_x(1)
-            .check_x(horizontal)
+            .try_add_x(1, horizontal)
             .unwrap_or_else(|| o.set_x(0))
             .sub_y(1),
         (AxisDQ::SW, true) => o
@@ -281,8 +272,7 @@ fn move_coord_q_lew(
         (AxisDQ::NW, true) => o
             .sub_x(1)
             .unwrap_or_else(|| o.set_x(horizontal - 1))
-            .add_y(1)
-            .check_y(vertical),
+            .try_add_y(1, vertical),
     }
     .map(|o| HexOffset(o))
     .ok_or(())
@@ -325,6 +315,15 @@ impl<T, L, const H: usize, const V: usize> Default for ConstHexOffsetShape<T, L,
     }
 }
 
+#[cfg(feature = "const-generic-wrap")]
+impl<T, L, const H: usize, const V: usize> ConstShapeDims for ConstHexOffsetShape<T, L, H, V>
+where
+    Self: Shape,
+{
+    const H: usize = H;
+    const V: usize = V;
+}
+
 impl<B, H, V> Shape for HexOffsetShape<B, (), H, V>
 where
     B: HexOffsetShapeBase,
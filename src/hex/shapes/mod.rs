@@ -12,6 +12,10 @@ pub enum AxisR {
     SE = 2,
 }
 
+impl AxisNames for AxisR {
+    const NAMES: &'static [&'static str] = &["NE", "E", "SE"];
+}
+
 impl Axis for AxisR {
     const COUNT: usize = 3;
     const DIRECTED: bool = false;
@@ -75,6 +79,10 @@ pub enum AxisDR {
     NW = 5,
 }
 
+impl AxisNames for AxisDR {
+    const NAMES: &'static [&'static str] = &["NE", "E", "SE", "SW", "W", "NW"];
+}
+
 impl Axis for AxisDR {
     const COUNT: usize = 6;
     const DIRECTED: bool = true;
@@ -146,6 +154,10 @@ pub enum AxisQ {
     SE = 2,
 }
 
+impl AxisNames for AxisQ {
+    const NAMES: &'static [&'static str] = &["N", "NE", "SE"];
+}
+
 impl Axis for AxisQ {
     const COUNT: usize = 3;
     const DIRECTED: bool = false;
@@ -209,6 +221,10 @@ pub enum AxisDQ {
     NW = 5,
 }
 
+impl AxisNames for AxisDQ {
+    const NAMES: &'static [&'static str] = &["N", "NE", "SE", "S", "SW", "NW"];
+}
+
 impl Axis for AxisDQ {
     const COUNT: usize = 6;
     const DIRECTED: bool = true;
@@ -309,7 +325,7 @@ impl OE for OddQ {
     const IS_EVEN: bool = false;
 }
 impl RQ for OddQ {
-    const IS_FLAT_TOP: bool = false;
+    const IS_FLAT_TOP: bool = true;
 }
 
 /// Flat-top + Even Shape.
@@ -319,7 +335,7 @@ impl OE for EvenQ {
     const IS_EVEN: bool = true;
 }
 impl RQ for EvenQ {
-    const IS_FLAT_TOP: bool = false;
+    const IS_FLAT_TOP: bool = true;
 }
 
 /// Marker to show the graph have loop.
@@ -334,6 +350,16 @@ impl LoopMarker for LoopEW {}
 #[deprecated(note = "Use LoopEW instead.")]
 pub type LEW = LoopEW;
 
+///Marker for N-S direction Loop.
+///
+/// For the point-top layouts ([`OddR`] and [`EvenR`]) the vertical size should be even,
+/// as the rows are shifted alternately and the rows at the both ends must be shifted differently to be connected.
+/// Otherwise the neighbors across the border are not consistent with the hex geometry.
+/// The flat-top layouts ([`OddQ`] and [`EvenQ`]) have no such constraint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoopNS {}
+impl LoopMarker for LoopNS {}
+
 ///Marker to tell the graph is directed.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DirectedMarker<T>(PhantomData<fn() -> T>);
@@ -344,3 +370,18 @@ impl<T: OE> OE for DirectedMarker<T> {
 impl<T: RQ> RQ for DirectedMarker<T> {
     const IS_FLAT_TOP: bool = T::IS_FLAT_TOP;
 }
+
+/// Marker to make some axes of the graph one-way.
+/// The graph is directed like [`DirectedMarker`].
+/// The axes whose bit of [`to_index`](`Axis::to_index`) of the undirected axis is set in `MASK`
+/// only have the edges to the forward direction, and the other axes have the edges to both directions.
+/// For example, `PartiallyDirected<OddR, 0b001>` makes `NE` one-way and `SW` can't be moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PartiallyDirected<T, const MASK: u8>(PhantomData<fn() -> T>);
+impl<T: OE, const MASK: u8> OE for PartiallyDirected<T, MASK> {
+    const IS_EVEN: bool = T::IS_EVEN;
+    const CONVERT_OFFSET: usize = T::CONVERT_OFFSET;
+}
+impl<T: RQ, const MASK: u8> RQ for PartiallyDirected<T, MASK> {
+    const IS_FLAT_TOP: bool = T::IS_FLAT_TOP;
+}
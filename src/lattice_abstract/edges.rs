@@ -35,6 +35,31 @@ impl<'a, C: Clone, E, D: Clone, A> Clone for EdgeReference<'a, C, E, D, A> {
 
 impl<'a, C: Copy, E, D: Copy, A> Copy for EdgeReference<'a, C, E, D, A> {}
 
+/// Wrapper of [`EdgeReference`] compared only by the edge weight, to be used in [`BinaryHeap`](`std::collections::BinaryHeap`).
+/// Wrap it with [`Reverse`](`std::cmp::Reverse`) to pop the edge with the minimum weight first.
+#[derive(Debug, Clone, Copy)]
+pub struct ByWeight<'a, C, E, D, A>(pub EdgeReference<'a, C, E, D, A>);
+
+impl<'a, C, E: PartialEq, D, A> PartialEq for ByWeight<'a, C, E, D, A> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.edge_weight == other.0.edge_weight
+    }
+}
+
+impl<'a, C, E: Eq, D, A> Eq for ByWeight<'a, C, E, D, A> {}
+
+impl<'a, C, E: PartialOrd, D, A> PartialOrd for ByWeight<'a, C, E, D, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.0.edge_weight.partial_cmp(other.0.edge_weight)
+    }
+}
+
+impl<'a, C, E: Ord, D, A> Ord for ByWeight<'a, C, E, D, A> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.edge_weight.cmp(other.0.edge_weight)
+    }
+}
+
 impl<'a, C, E, D, A> EdgeRef for EdgeReference<'a, C, E, D, A>
 where
     C: Copy,
@@ -69,6 +94,7 @@ pub struct Edges<'a, N, E, S: Shape, C = <S as Shape>::Coordinate, Dt = AxisDirM
     graph: &'a LatticeGraph<N, E, S>,
     node: C,
     offset: Offset,
+    index: usize,
     state: usize,
     directed: Dt,
 }
@@ -166,7 +192,12 @@ where
             } else {
                 S::Axis::UNDIRECTED_COUNT
             },
-            offset: offset.unwrap_or_else(|_| unsafe { unreachable_debug_checked() }),
+            index: match offset {
+                Ok(o) if g.has_connectivity_cache() => g.s.offset_to_index(o),
+                _ => 0,
+            },
+            // not used if the node is out of the graph.
+            offset: offset.unwrap_or_default(),
             directed: d,
         }
     }
@@ -175,12 +206,19 @@ where
     where
         Dt: Default,
     {
-        let offset = g.s.to_offset(a);
+        let offset =
+            g.s.to_offset(a)
+                .unwrap_or_else(|_| unreachable_debug_checked());
         Edges {
             graph: g,
             node: a,
             state: 0,
-            offset: offset.unwrap_or_else(|_| unreachable_debug_checked()),
+            index: if g.has_connectivity_cache() {
+                g.s.offset_to_index(offset)
+            } else {
+                0
+            },
+            offset,
             directed: Dt::default(),
         }
     }
@@ -206,14 +244,27 @@ where
         {
             unsafe {
                 let d = D::dir_from_index_unchecked(self.state);
-                let n = self.graph.s.move_coord(self.node, d.clone());
+                let n = if A::DIRECTED && self.directed.need_reverse() {
+                    // the source may not be movable from this node. (See `Shape::move_coord_reverse`)
+                    self.graph
+                        .s
+                        .move_coord_reverse(self.node, reverse_direction::<A>(d.clone()))
+                        .ok()
+                } else {
+                    self.graph
+                        .move_coord_cached(self.node, self.index, d.clone())
+                };
                 let st = self.state;
                 self.state += 1;
-                if let Ok(target) = n {
+                if let Some(target) = n {
                     let (nx, ne) =
                         self.directed
                             .get_raw_id(&self.graph.s, &d, self.offset, target, st);
-                    debug_assert_eq!(A::from_direction(d.clone()).to_index(), ne);
+                    // incoming edges of directed graph are stored in the backward axis of the target.
+                    debug_assert!(
+                        (A::DIRECTED && self.directed.need_reverse())
+                            || A::from_direction(d.clone()).to_index() == ne
+                    );
                     //let ne = S::Axis::from_direction(d.clone()).to_index();
                     let e = self.graph.edge_weight_unchecked_raw((nx, ne));
                     let (source_id, target_id) = if self.directed.need_reverse() {
@@ -265,6 +316,36 @@ where
     }
 }
 
+/// Edges from a source node and the source. See [`edges_grouped_by_source`](`LatticeGraph::edges_grouped_by_source`).
+pub type EdgesFromSource<'a, C, E, D, A> = (C, Vec<EdgeReference<'a, C, E, D, A>>);
+
+impl<N, E, S, C, D, A> LatticeGraph<N, E, S>
+where
+    C: Copy,
+    S: Shape<Coordinate = C, Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+{
+    /// Iterate the nodes with the edges from it, in the order of [`from_index`](`Shape::from_index`).
+    /// The nodes without any edges are skipped.
+    /// The edges are the same as [`edge_references`](`IntoEdgeReferences::edge_references`) in the same order,
+    /// so it only contains the edges to the forward direction if the graph is undirected.
+    pub fn edges_grouped_by_source(
+        &self,
+    ) -> impl Iterator<Item = EdgesFromSource<'_, C, E, D, A>> + '_ {
+        (0..self.s.node_count()).filter_map(move |i| {
+            let c = self.s.from_index(i);
+            let edges = unsafe { Edges::<N, E, S, C, AxisMarker>::new_unchecked(self, c) }
+                .collect::<Vec<_>>();
+            if edges.is_empty() {
+                None
+            } else {
+                Some((c, edges))
+            }
+        })
+    }
+}
+
 /// Edges connected to a node with [`Direction`](`petgraph::Direction`). See [`IntoEdgesDirected`].
 pub type EdgesDirected<'a, N, E, S> =
     Edges<'a, N, E, S, <S as Shape>::Coordinate, petgraph::Direction>;
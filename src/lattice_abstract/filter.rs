@@ -0,0 +1,258 @@
+use std::iter::FusedIterator;
+
+use petgraph::visit::{IntoEdgeReferences, IntoEdges, IntoNeighbors, IntoNodeIdentifiers};
+
+use super::*;
+
+/// View of a graph which hides the edges whose weight doesn't match the predicate, without mutating the graph.
+/// The neighbors across the hidden edges are also hidden.
+///
+/// It can be used for any graph in this crate which implements [`IntoEdges`] for the reference,
+/// like [`LatticeGraph`] or [`SquareGraph`](`crate::SquareGraph`).
+/// ```
+/// # use lattice_graph::lattice_abstract::{square::*, FilterEdges};
+/// # use petgraph::visit::IntoNeighbors;
+/// let graph = SquareGraphAbstract::<(), u32>::new_with(SquareShape::new(3, 1), |_| (), |c, _| c.0.horizontal() as u32);
+/// let filtered = FilterEdges::new(&graph, |e: &u32| *e < 1);
+/// assert_eq!(filtered.neighbors((1, 0).into()).collect::<Vec<_>>(), vec![SquareOffset::from((0, 0))]);
+/// ```
+#[derive(Debug)]
+pub struct FilterEdges<'a, G, P> {
+    graph: &'a G,
+    predicate: P,
+}
+
+impl<'a, G, P: Clone> Clone for FilterEdges<'a, G, P> {
+    fn clone(&self) -> Self {
+        Self {
+            graph: self.graph,
+            predicate: self.predicate.clone(),
+        }
+    }
+}
+
+impl<'a, G, P> FilterEdges<'a, G, P> {
+    /// Creates a view of the graph showing only the edges whose weight matches `predicate`.
+    pub fn new(graph: &'a G, predicate: P) -> Self {
+        Self { graph, predicate }
+    }
+
+    /// Get the inner graph.
+    pub fn graph(&self) -> &'a G {
+        self.graph
+    }
+}
+
+impl<'a, G: GraphBase, P> GraphBase for FilterEdges<'a, G, P> {
+    type NodeId = G::NodeId;
+    type EdgeId = G::EdgeId;
+}
+
+impl<'a, G: Data, P> Data for FilterEdges<'a, G, P> {
+    type NodeWeight = G::NodeWeight;
+    type EdgeWeight = G::EdgeWeight;
+}
+
+impl<'a, G, P> DataMap for FilterEdges<'a, G, P>
+where
+    G: DataMap,
+    P: Fn(&G::EdgeWeight) -> bool,
+{
+    fn node_weight(&self, id: Self::NodeId) -> Option<&Self::NodeWeight> {
+        self.graph.node_weight(id)
+    }
+
+    fn edge_weight(&self, id: Self::EdgeId) -> Option<&Self::EdgeWeight> {
+        self.graph.edge_weight(id).filter(|e| (self.predicate)(e))
+    }
+}
+
+impl<'a, G: Visitable, P> Visitable for FilterEdges<'a, G, P> {
+    type Map = G::Map;
+
+    fn visit_map(&self) -> Self::Map {
+        self.graph.visit_map()
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        self.graph.reset_map(map)
+    }
+}
+
+impl<'a, G: NodeCount, P> NodeCount for FilterEdges<'a, G, P> {
+    fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+}
+
+impl<'a, G, P> IntoNodeIdentifiers for &FilterEdges<'a, G, P>
+where
+    &'a G: IntoNodeIdentifiers<NodeId = G::NodeId>,
+    G: GraphBase,
+{
+    type NodeIdentifiers = <&'a G as IntoNodeIdentifiers>::NodeIdentifiers;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        self.graph.node_identifiers()
+    }
+}
+
+/// Edges of [`FilterEdges`] skipping the edges which don't match the predicate.
+/// See [`IntoEdges`] and [`IntoEdgeReferences`].
+#[derive(Debug, Clone)]
+pub struct FilteredEdges<'b, I, P> {
+    iter: I,
+    predicate: &'b P,
+}
+
+impl<'b, I, P> Iterator for FilteredEdges<'b, I, P>
+where
+    I: Iterator,
+    I::Item: EdgeRef,
+    P: Fn(&<I::Item as EdgeRef>::Weight) -> bool,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let p = self.predicate;
+        self.iter.find(|e| p(e.weight()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<'b, I, P> FusedIterator for FilteredEdges<'b, I, P>
+where
+    I: FusedIterator,
+    I::Item: EdgeRef,
+    P: Fn(&<I::Item as EdgeRef>::Weight) -> bool,
+{
+}
+
+/// Neighbors of the node in [`FilterEdges`]. See [`IntoNeighbors`].
+#[derive(Debug, Clone)]
+pub struct FilteredNeighbors<'b, I, P> {
+    edges: FilteredEdges<'b, I, P>,
+}
+
+impl<'b, I, P> Iterator for FilteredNeighbors<'b, I, P>
+where
+    I: Iterator,
+    I::Item: EdgeRef,
+    P: Fn(&<I::Item as EdgeRef>::Weight) -> bool,
+{
+    type Item = <I::Item as EdgeRef>::NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.edges.next().map(|e| e.target())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.edges.size_hint()
+    }
+}
+
+impl<'b, I, P> FusedIterator for FilteredNeighbors<'b, I, P>
+where
+    I: FusedIterator,
+    I::Item: EdgeRef,
+    P: Fn(&<I::Item as EdgeRef>::Weight) -> bool,
+{
+}
+
+impl<'a, 'b, G, P> IntoEdgeReferences for &'b FilterEdges<'a, G, P>
+where
+    &'a G: IntoEdgeReferences<NodeId = G::NodeId, EdgeId = G::EdgeId, EdgeWeight = G::EdgeWeight>,
+    G: Data,
+    P: Fn(&G::EdgeWeight) -> bool,
+{
+    type EdgeRef = <&'a G as IntoEdgeReferences>::EdgeRef;
+    type EdgeReferences = FilteredEdges<'b, <&'a G as IntoEdgeReferences>::EdgeReferences, P>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        FilteredEdges {
+            iter: self.graph.edge_references(),
+            predicate: &self.predicate,
+        }
+    }
+}
+
+impl<'a, 'b, G, P> IntoNeighbors for &'b FilterEdges<'a, G, P>
+where
+    &'a G: IntoEdges<NodeId = G::NodeId, EdgeId = G::EdgeId, EdgeWeight = G::EdgeWeight>,
+    G: Data,
+    P: Fn(&G::EdgeWeight) -> bool,
+{
+    type Neighbors = FilteredNeighbors<'b, <&'a G as IntoEdges>::Edges, P>;
+
+    fn neighbors(self, a: Self::NodeId) -> Self::Neighbors {
+        FilteredNeighbors {
+            edges: self.edges(a),
+        }
+    }
+}
+
+impl<'a, 'b, G, P> IntoEdges for &'b FilterEdges<'a, G, P>
+where
+    &'a G: IntoEdges<NodeId = G::NodeId, EdgeId = G::EdgeId, EdgeWeight = G::EdgeWeight>,
+    G: Data,
+    P: Fn(&G::EdgeWeight) -> bool,
+{
+    type Edges = FilteredEdges<'b, <&'a G as IntoEdges>::Edges, P>;
+
+    fn edges(self, a: Self::NodeId) -> Self::Edges {
+        FilteredEdges {
+            iter: self.graph.edges(a),
+            predicate: &self.predicate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice_abstract::square::{
+        SquareAxis, SquareGraphAbstract, SquareOffset, SquareShape,
+    };
+    use petgraph::algo::astar;
+
+    #[test]
+    fn filter_edges() {
+        // the edges from the column 1 to the column 2 cost 6 except for the top row.
+        let graph = SquareGraphAbstract::<(), u32>::new_with(
+            SquareShape::new(4, 5),
+            |_| (),
+            |c, a| {
+                if a == SquareAxis::X && c.0.horizontal() == 1 && c.0.vertical() != 4 {
+                    6
+                } else {
+                    1
+                }
+            },
+        );
+        let filtered = FilterEdges::new(&graph, |e: &u32| *e <= 5);
+        let c = SquareOffset::from((1, 0));
+        assert_eq!(graph.neighbors(c).count(), 3);
+        assert_eq!(
+            filtered.neighbors(c).collect::<Vec<_>>(),
+            vec![SquareOffset::from((1, 1)), (0, 0).into()]
+        );
+        assert_eq!(filtered.neighbors((2, 1).into()).count(), 3);
+        assert_eq!(filtered.edge_weight((c, SquareAxis::X)), None);
+        assert_eq!(filtered.edge_weight((c, SquareAxis::Y)), Some(&1));
+        assert_eq!(
+            filtered.edge_references().count(),
+            graph.edge_references().count() - 4
+        );
+        assert_eq!(filtered.node_identifiers().count(), 20);
+
+        let (start, goal) = (SquareOffset::from((0, 0)), SquareOffset::from((3, 0)));
+        let (cost, _) = astar(&graph, start, |n| n == goal, |e| *e.weight(), |_| 0).unwrap();
+        assert_eq!(cost, 8);
+        let (cost, path) = astar(&filtered, start, |n| n == goal, |e| *e.weight(), |_| 0).unwrap();
+        assert_eq!(cost, 11);
+        assert!(path.contains(&(1, 4).into()) && path.contains(&(2, 4).into()));
+    }
+}
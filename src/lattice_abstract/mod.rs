@@ -1,22 +1,46 @@
 //! Module for Abstract 2D Lattice Graph. It is used inside by other lattice graph in other modules like [`hex`](`crate::hex`).
 //! Use it when you want to define your own lattice graph, or to use the concreate visit iterator structs for traits in [`visit`](`petgraph::visit`).
 
-use crate::{fixedvec2d::*, unreachable_debug_checked};
+use crate::{
+    algo::{dijkstra_reuse, PathScratch},
+    fixedvec2d::*,
+    unreachable_debug_checked,
+};
 use fixedbitset::FixedBitSet;
 use petgraph::{
+    algo::Measure,
     data::{DataMap, DataMapMut},
-    visit::{Data, GraphBase, GraphProp, IntoNodeIdentifiers, NodeCount, VisitMap, Visitable},
+    visit::{
+        Data, EdgeRef, GraphBase, GraphProp, IntoEdges, IntoNodeIdentifiers, NodeCount, VisitMap,
+        Visitable,
+    },
     EdgeType,
 };
-use std::{marker::PhantomData, mem::MaybeUninit, num::NonZeroUsize, ptr::drop_in_place};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    num::NonZeroUsize,
+    ptr::drop_in_place,
+};
 mod edges;
-pub use edges::{EdgeReference, EdgeReferences, Edges, EdgesDirected};
+pub use edges::{ByWeight, EdgeReference, EdgeReferences, Edges, EdgesDirected, EdgesFromSource};
+mod filter;
+pub use filter::*;
 mod neighbors;
 pub use neighbors::*;
 mod nodes;
 pub use nodes::*;
+mod portal;
+pub use portal::*;
 pub mod shapes;
 pub(crate) use shapes::*;
+#[cfg(feature = "scene_json")]
+mod scene;
+#[cfg(feature = "serde")]
+mod serde_impl;
 pub mod square;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -27,13 +51,60 @@ pub struct LatticeGraph<N, E, S: Shape> {
     nodes: FixedVec2D<N>,
     edges: Vec<FixedVec2D<E>>,
     s: S,
+    connectivity: ConnectivityCache,
+}
+
+/// Cache of whether each node has an edge to each direction.
+/// See [`with_connectivity_cache`](`LatticeGraph::with_connectivity_cache`).
+/// It is ignored when comparing or hashing the graph.
+#[derive(Debug, Clone, Default)]
+struct ConnectivityCache(Option<FixedBitSet>);
+
+impl PartialEq for ConnectivityCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ConnectivityCache {}
+
+impl Hash for ConnectivityCache {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
+/// Error of [`LatticeGraph::try_new`] that the graph can't be built with the [`Shape`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GraphBuildError {
+    /// The horizontal node count is 0.
+    ZeroHorizontal,
+    /// The node count or the bytes of the node or edge array overflows.
+    Overflow { horizontal: usize, vertical: usize },
+}
+
+impl std::fmt::Display for GraphBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphBuildError::ZeroHorizontal => write!(f, "the horizontal node count is 0"),
+            GraphBuildError::Overflow {
+                horizontal,
+                vertical,
+            } => write!(f, "the shape {}x{} is too large", horizontal, vertical),
+        }
+    }
 }
 
+impl std::error::Error for GraphBuildError {}
+
 impl<N, E, S: Shape> LatticeGraph<N, E, S> {
     /// Creates a graph from raw data. This api might change.
     #[doc(hidden)]
     pub unsafe fn new_raw(nodes: FixedVec2D<N>, edges: Vec<FixedVec2D<E>>, s: S) -> Self {
-        Self { nodes, edges, s }
+        Self {
+            nodes,
+            edges,
+            s,
+            connectivity: ConnectivityCache::default(),
+        }
     }
 
     /// Creates a graph with uninitalized node and edge weight data.
@@ -49,24 +120,52 @@ impl<N, E, S: Shape> LatticeGraph<N, E, S> {
                 s.vertical(),
             ))
         }
-        Self { nodes, edges, s }
+        Self::new_raw(nodes, edges, s)
     }
 
     /// Creates a graph with node and edge weight data set to [`default`](`Default::default`).
+    ///
+    /// # Panics
+    /// Panics if the shape is invalid. See [`try_new`](`Self::try_new`).
     pub fn new(s: S) -> Self
     where
         N: Default,
         E: Default,
     {
-        Self::new_with(s, |_| N::default(), |_, _| E::default())
+        Self::try_new(s).expect("invalid shape")
     }
 
     /// Creates a graph with node and edge weight data from the coordinate.
-    pub fn new_with<FN, FE>(s: S, mut n: FN, mut e: FE) -> Self
+    ///
+    /// # Panics
+    /// Panics if the shape is invalid. See [`try_new`](`Self::try_new`).
+    pub fn new_with<FN, FE>(s: S, n: FN, e: FE) -> Self
     where
         FN: FnMut(S::Coordinate) -> N,
         FE: FnMut(S::Coordinate, S::Axis) -> E, // change to E ?
     {
+        Self::try_new_with(s, n, e).expect("invalid shape")
+    }
+
+    /// Creates a graph with node and edge weight data set to [`default`](`Default::default`).
+    /// Returns [`GraphBuildError`] without allocating if the horizontal node count is 0,
+    /// or the node count or the bytes of the arrays overflows.
+    pub fn try_new(s: S) -> Result<Self, GraphBuildError>
+    where
+        N: Default,
+        E: Default,
+    {
+        Self::try_new_with(s, |_| N::default(), |_, _| E::default())
+    }
+
+    /// Creates a graph with node and edge weight data from the coordinate.
+    /// See [`try_new`](`Self::try_new`) for the errors.
+    pub fn try_new_with<FN, FE>(s: S, mut n: FN, mut e: FE) -> Result<Self, GraphBuildError>
+    where
+        FN: FnMut(S::Coordinate) -> N,
+        FE: FnMut(S::Coordinate, S::Axis) -> E,
+    {
+        Self::check_shape(&s)?;
         let mut uninit = unsafe { Self::new_uninit(s) };
         let s = &uninit.s;
         let nodes = uninit.nodes.mut_1d();
@@ -91,13 +190,644 @@ impl<N, E, S: Shape> LatticeGraph<N, E, S> {
                 });
             }
         }
-        uninit
+        Ok(uninit)
+    }
+
+    fn check_shape(s: &S) -> Result<(), GraphBuildError> {
+        let (horizontal, vertical) = (s.horizontal(), s.vertical());
+        if horizontal == 0 {
+            return Err(GraphBuildError::ZeroHorizontal);
+        }
+        let size = std::mem::size_of::<N>().max(std::mem::size_of::<E>());
+        match horizontal
+            .checked_mul(vertical)
+            .and_then(|n| n.checked_mul(size))
+        {
+            Some(bytes) if bytes <= isize::MAX as usize => Ok(()),
+            _ => Err(GraphBuildError::Overflow {
+                horizontal,
+                vertical,
+            }),
+        }
     }
 
     /// Get a reference to the lattice graph's s.
     pub fn shape(&self) -> &S {
         &self.s
     }
+
+    /// Returns the bytes allocated for the node and edge arrays.
+    /// See [`FixedVec2D::memory_usage`].
+    pub fn memory_usage(&self) -> usize {
+        self.nodes.memory_usage()
+            + self
+                .edges
+                .iter()
+                .map(FixedVec2D::memory_usage)
+                .sum::<usize>()
+    }
+
+    /// Get a reference to the raw edge arrays. `[axis][horizontal][vertical]`
+    ///
+    /// The array at index `i` is the edges of [`Axis::from_index(i)`](`Axis::from_index`) from the node at the offset.
+    /// The slots where the edge doesn't exist (out of the border) are uninitialized, so don't read them.
+    /// Use [`move_coord`](`Shape::move_coord`) to check whether the edge exists.
+    pub fn edge_arrays(&self) -> &[FixedVec2D<E>] {
+        &self.edges
+    }
+
+    /// Get a mutable reference to the raw edge arrays. See [`edge_arrays`](`Self::edge_arrays`).
+    ///
+    /// # Safety
+    /// Don't read or drop the uninitialized slots where the edge doesn't exist
+    /// (e.g. assigning to them will drop the uninitialized value), and don't replace the arrays with ones of different size.
+    pub unsafe fn edge_arrays_mut(&mut self) -> &mut [FixedVec2D<E>] {
+        &mut self.edges
+    }
+
+    /// Returns the count of the axis. See [`Axis::COUNT`].
+    pub fn axis_count(&self) -> usize {
+        S::Axis::COUNT
+    }
+
+    /// Returns the count of the directions from each node. See [`Axis::UNDIRECTED_COUNT`].
+    pub fn direction_count(&self) -> usize {
+        S::Axis::UNDIRECTED_COUNT
+    }
+
+    /// Returns the name of the axis with the index. See [`AxisNames`].
+    pub fn axis_name(&self, index: usize) -> Option<&'static str>
+    where
+        S::Axis: AxisNames,
+    {
+        <S::Axis as AxisNames>::NAMES.get(index).copied()
+    }
+
+    /// Creates a new graph with the same shape, mapping the node and edge weights.
+    /// `fedge` is only called for the edges which exist.
+    pub fn map<N2, E2, FN, FE>(&self, mut fnode: FN, mut fedge: FE) -> LatticeGraph<N2, E2, S>
+    where
+        FN: FnMut(S::Coordinate, &N) -> N2,
+        FE: FnMut(S::Coordinate, S::Axis, &E) -> E2,
+    {
+        let mut g = LatticeGraph::new_with(
+            self.s.clone(),
+            |c| fnode(c, unsafe { self.node_weight_unchecked(c) }),
+            |c, a| fedge(c, a, unsafe { self.edge_weight_unchecked((c, a)) }),
+        );
+        g.connectivity = self.connectivity.clone();
+        g
+    }
+
+    /// Returns all the coordinates in the graph sorted by the distance from `center` with `metric`.
+    /// The coordinates with the same distance are in the order of [`from_index`](`Shape::from_index`).
+    pub fn coords_by_distance<M>(&self, center: S::Coordinate, metric: M) -> Vec<S::Coordinate>
+    where
+        M: Metric<S::Coordinate>,
+    {
+        let mut coords = (0..self.s.node_count())
+            .map(|i| self.s.from_index(i))
+            .collect::<Vec<_>>();
+        coords.sort_by_cached_key(|c| metric.distance(center, *c));
+        coords
+    }
+
+    /// Check whether the other graph has the same size of this graph.
+    /// Loop and directedness are the same since they share the shape type `S`.
+    pub fn same_shape_as<N2, E2>(&self, other: &LatticeGraph<N2, E2, S>) -> bool {
+        self.s.horizontal() == other.s.horizontal() && self.s.vertical() == other.s.vertical()
+    }
+
+    /// Builds a cache of whether each node has an edge to each direction.
+    /// [`neighbors`](`petgraph::visit::IntoNeighbors::neighbors`) and [`edges`](`petgraph::visit::IntoEdges::edges`)
+    /// will use it to skip checking the border in [`move_coord`](`Shape::move_coord`).
+    /// It needs `node_count * UNDIRECTED_COUNT` bits.
+    pub fn with_connectivity_cache(mut self) -> Self {
+        self.connectivity = ConnectivityCache(Some(self.connectivity_bits()));
+        self
+    }
+
+    /// Bits of whether each node has an edge to each direction, indexed by `index * UNDIRECTED_COUNT + dir_to_index`.
+    pub(crate) fn connectivity_bits(&self) -> FixedBitSet {
+        if let Some(cache) = &self.connectivity.0 {
+            return cache.clone();
+        }
+        let dc = S::Axis::UNDIRECTED_COUNT;
+        let mut cache = FixedBitSet::with_capacity(self.s.node_count() * dc);
+        for i in 0..self.s.node_count() {
+            let c = self.s.from_index(i);
+            for d in 0..dc {
+                let dir = unsafe { <S::Axis as Axis>::Direction::dir_from_index_unchecked(d) };
+                if self.s.move_coord(c, dir).is_ok() {
+                    cache.insert(i * dc + d);
+                }
+            }
+        }
+        cache
+    }
+
+    /// Returns whether the graph has the cache built by [`with_connectivity_cache`](`Self::with_connectivity_cache`).
+    pub fn has_connectivity_cache(&self) -> bool {
+        self.connectivity.0.is_some()
+    }
+
+    /// Move the coordinate to the direction, using the connectivity cache if it exists.
+    /// `index` is the index of `coord` and only used when the cache exists.
+    #[inline]
+    pub(crate) unsafe fn move_coord_cached(
+        &self,
+        coord: S::Coordinate,
+        index: usize,
+        dir: <S::Axis as Axis>::Direction,
+    ) -> Option<S::Coordinate> {
+        match &self.connectivity.0 {
+            Some(cache) => {
+                if cache.contains(index * S::Axis::UNDIRECTED_COUNT + dir.dir_to_index()) {
+                    Some(self.s.move_coord_unchecked(coord, dir))
+                } else {
+                    None
+                }
+            }
+            None => self.s.move_coord(coord, dir).ok(),
+        }
+    }
+
+    /// Overwrite the node weights from the iterator in the storage order (the order of [`from_index`](`Shape::from_index`)).
+    /// Items after `node_count` are ignored and the nodes are left unchanged if the iterator is shorter.
+    pub fn write_nodes(&mut self, values: impl IntoIterator<Item = N>) {
+        for (n, x) in self.nodes.mut_1d().iter_mut().zip(values) {
+            *n = x;
+        }
+    }
+
+    /// Overwrite all the node weights with `value`.
+    pub fn fill_nodes(&mut self, value: N)
+    where
+        N: Clone,
+    {
+        self.nodes.mut_1d().fill(value)
+    }
+
+    /// Overwrite the weights of all the edges which exist with `value`.
+    /// The uninitialized slots on the border are not touched.
+    pub fn fill_edges(&mut self, value: E)
+    where
+        E: Clone,
+    {
+        let s = &self.s;
+        for (j, edges) in self.edges.iter_mut().enumerate() {
+            let dir = unsafe { <S::Axis as Axis>::from_index_unchecked(j) }.foward();
+            let edges = edges.mut_2d();
+            for i in 0..s.node_count() {
+                let offset = s.index_to_offset(i);
+                if s.move_coord(s.from_offset(offset), dir.clone()).is_ok() {
+                    // SAFETY : the slot is initialized as the edge exists.
+                    unsafe {
+                        *edges
+                            .get_unchecked_mut(offset.horizontal)
+                            .get_unchecked_mut(offset.vertical) = value.clone();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the minimum and maximum of the node weights.
+    pub fn node_weight_bounds(&self) -> Option<(&N, &N)>
+    where
+        N: Ord,
+    {
+        min_max(self.nodes.ref_1d().iter())
+    }
+
+    /// Returns the minimum and maximum of the edge weights.
+    /// Returns [`None`] if the graph has no edges.
+    pub fn edge_weight_bounds(&self) -> Option<(&E, &E)>
+    where
+        E: Ord,
+    {
+        min_max(self.existing_edge_weights())
+    }
+
+    /// Iterate the weights of the edges which exist, skipping uninitialized slots on the border.
+    fn existing_edge_weights(&self) -> impl Iterator<Item = &E> {
+        self.edges_by_offset().map(|(_, _, e)| e)
+    }
+
+    /// Iterate the edges which exist as they are stored, with the [`Offset`] of the source node and the index of the axis.
+    /// The edge of `(offset, axis)` is the one to the forward direction of the axis from the node of the `offset`,
+    /// so it can be accessed by [`edge_weight`](`DataMap::edge_weight`) with `(shape.from_offset(offset), axis)`.
+    pub fn edges_by_offset(&self) -> impl Iterator<Item = (Offset, usize, &E)> {
+        let s = &self.s;
+        let edges = &self.edges;
+        (0..s.node_count()).flat_map(move |i| {
+            let offset = s.index_to_offset(i);
+            let c = s.from_offset(offset);
+            (0..S::Axis::COUNT).filter_map(move |j| {
+                let a = unsafe { <S::Axis as Axis>::from_index_unchecked(j) };
+                if s.move_coord(c, a.foward()).is_ok() {
+                    Some((offset, j, unsafe {
+                        edges
+                            .get_unchecked(j)
+                            .ref_2d()
+                            .get_unchecked(offset.horizontal)
+                            .get_unchecked(offset.vertical)
+                    }))
+                } else {
+                    None
+                }
+            })
+        })
+    }
+}
+
+fn min_max<T: Ord + Copy, I: Iterator<Item = T>>(mut iter: I) -> Option<(T, T)> {
+    let first = iter.next()?;
+    Some(iter.fold((first, first), |(min, max), x| {
+        if x < min {
+            (x, max)
+        } else if x > max {
+            (min, x)
+        } else {
+            (min, max)
+        }
+    }))
+}
+
+impl<N, E, S, A, D> LatticeGraph<N, E, S>
+where
+    S: Shape<Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+{
+    /// Get the neighbor of the node to the direction, with the flag whether it wraps around the border of loop.
+    /// Wrapping is detected by the difference of the [`Offset`] larger than 1,
+    /// so wrapping in the axis with length 2 or less is not detected.
+    pub fn neighbor_in_dir(&self, c: S::Coordinate, dir: D) -> Option<(S::Coordinate, bool)> {
+        let o = self.s.to_offset(c).ok()?;
+        let t = self.s.move_coord(c, dir).ok()?;
+        let to = unsafe { self.s.to_offset_unchecked(t) };
+        let far = |a: usize, b: usize| a.max(b) - a.min(b) > 1;
+        Some((
+            t,
+            far(o.horizontal, to.horizontal) || far(o.vertical, to.vertical),
+        ))
+    }
+
+    /// Returns the count of the edges from the node, same as the count of [`neighbors`](`petgraph::visit::IntoNeighbors::neighbors`).
+    /// It is the count of the outgoing edges for the directed graph. Returns 0 if the node is not in the graph.
+    pub fn degree(&self, c: S::Coordinate) -> usize {
+        let mut count = 0;
+        self.for_each_neighbor(c, |_| count += 1);
+        count
+    }
+
+    /// Check whether the node is on the border, that is, some direction from it is out of the graph.
+    /// No node is on the border for the shape which loops in all axes.
+    /// Returns `false` if the node is not in the graph.
+    pub fn is_border(&self, c: S::Coordinate) -> bool {
+        self.s.to_offset(c).is_ok() && self.degree(c) < A::UNDIRECTED_COUNT
+    }
+
+    /// Iterate the nodes on the border in the order of [`from_index`](`Shape::from_index`). See [`is_border`](`Self::is_border`).
+    pub fn border_nodes(&self) -> impl Iterator<Item = S::Coordinate> + '_ {
+        (0..self.s.node_count())
+            .map(move |i| self.s.from_index(i))
+            .filter(move |c| self.is_border(*c))
+    }
+
+    /// Call `f` with each neighbor of the node. This is same as [`neighbors`](`petgraph::visit::IntoNeighbors::neighbors`)
+    /// but uses a callback instead of an iterator.
+    pub fn for_each_neighbor(&self, c: S::Coordinate, mut f: impl FnMut(S::Coordinate)) {
+        let index = match self.s.to_offset(c) {
+            Ok(o) if self.has_connectivity_cache() => self.s.offset_to_index(o),
+            Ok(_) => 0,
+            Err(_) => return,
+        };
+        for i in 0..A::UNDIRECTED_COUNT {
+            unsafe {
+                let d = D::dir_from_index_unchecked(i);
+                if let Some(t) = self.move_coord_cached(c, index, d) {
+                    f(t)
+                }
+            }
+        }
+    }
+
+    /// Call `f` with each edge connected to the node. This is same as [`edges`](`IntoEdges::edges`)
+    /// but uses a callback instead of an iterator.
+    pub fn for_each_edge<'a>(
+        &'a self,
+        c: S::Coordinate,
+        mut f: impl FnMut(EdgeReference<'a, S::Coordinate, E, D, A>),
+    ) {
+        let offset = match self.s.to_offset(c) {
+            Ok(o) => o,
+            Err(_) => return,
+        };
+        let index = if self.has_connectivity_cache() {
+            self.s.offset_to_index(offset)
+        } else {
+            0
+        };
+        for i in 0..A::UNDIRECTED_COUNT {
+            unsafe {
+                let d = D::dir_from_index_unchecked(i);
+                if let Some(t) = self.move_coord_cached(c, index, d) {
+                    let o = if A::is_forward_direction(&d) {
+                        offset
+                    } else {
+                        self.s.to_offset_unchecked(t)
+                    };
+                    let ax = A::from_direction(d).to_index();
+                    f(EdgeReference {
+                        source_id: c,
+                        target_id: t,
+                        edge_weight: self.edge_weight_unchecked_raw((o, ax)),
+                        direction: d,
+                        axis: PhantomData,
+                    })
+                }
+            }
+        }
+    }
+
+    /// Iterate the direction, the target and the weight of each edge connected to the node.
+    /// This yields the same edges in the same order as [`edges`](`IntoEdges::edges`)
+    /// without constructing [`EdgeReference`].
+    pub fn adjacencies(
+        &self,
+        c: S::Coordinate,
+    ) -> impl Iterator<Item = (D, S::Coordinate, &E)> + '_ {
+        let offset = self.s.to_offset(c);
+        let (index, count) = match offset {
+            Ok(o) if self.has_connectivity_cache() => {
+                (self.s.offset_to_index(o), A::UNDIRECTED_COUNT)
+            }
+            Ok(_) => (0, A::UNDIRECTED_COUNT),
+            Err(_) => (0, 0),
+        };
+        // not used if the node is out of the graph.
+        let offset = offset.unwrap_or_default();
+        let mut i = 0;
+        std::iter::from_fn(move || {
+            while i < count {
+                unsafe {
+                    let d = D::dir_from_index_unchecked(i);
+                    let st = i;
+                    i += 1;
+                    if let Some(t) = self.move_coord_cached(c, index, d) {
+                        let raw = if A::is_forward_direction(&d) {
+                            (offset, st)
+                        } else {
+                            (self.s.to_offset_unchecked(t), st - A::COUNT)
+                        };
+                        return Some((d, t, self.edge_weight_unchecked_raw(raw)));
+                    }
+                }
+            }
+            None
+        })
+    }
+
+    /// Iterate the neighbors connected with the edge whose weight is less than or equal to `max_cost`.
+    pub fn passable_neighbors(
+        &self,
+        c: S::Coordinate,
+        max_cost: E,
+    ) -> impl Iterator<Item = S::Coordinate> + '_
+    where
+        E: PartialOrd,
+    {
+        self.edges(c)
+            .filter(move |e| *e.weight() <= max_cost)
+            .map(|e| e.target())
+    }
+
+    /// Returns the neighbor connected with the edge of the minimum weight and the weight.
+    /// If there are some edges with the same weight, the first one in the order of directions is returned.
+    pub fn cheapest_neighbor(&self, c: S::Coordinate) -> Option<(S::Coordinate, &E)>
+    where
+        E: Ord,
+    {
+        self.edges(c)
+            .min_by(|a, b| a.edge_weight.cmp(b.edge_weight))
+            .map(|e| (e.target_id, e.edge_weight))
+    }
+
+    /// Returns the nearest node from `start` whose coordinate and weight match `pred`, by breadth first search.
+    /// `start` itself is checked first. If some nodes with the same hop count match,
+    /// the first one found in the order of directions is returned.
+    /// Returns [`None`] if no reachable node matches or `start` is not in the graph.
+    pub fn nearest_matching(
+        &self,
+        start: S::Coordinate,
+        pred: impl Fn(S::Coordinate, &N) -> bool,
+    ) -> Option<S::Coordinate> {
+        let mut visited = FixedBitSet::with_capacity(self.s.node_count());
+        visited.insert(self.s.to_index(start)?);
+        let mut queue = std::collections::VecDeque::new();
+        queue.push_back(start);
+        while let Some(c) = queue.pop_front() {
+            // SAFETY : only the nodes in the graph are pushed.
+            if pred(c, unsafe { self.node_weight_unchecked(c) }) {
+                return Some(c);
+            }
+            self.for_each_neighbor(c, |t| {
+                if let Some(i) = self.s.to_index(t) {
+                    if !visited.put(i) {
+                        queue.push_back(t);
+                    }
+                }
+            });
+        }
+        None
+    }
+
+    /// Returns the nodes reachable from `start` within `max_cost` with the cost of the shortest path, by dijkstra
+    /// which stops expanding past `max_cost`. `start` is included with the cost 0.
+    /// Give the cost larger than `max_cost` (like [`u32::MAX`]) to the impassable edges.
+    /// Returns an empty map if `start` is not in the graph.
+    pub fn reachable_within(
+        &self,
+        start: S::Coordinate,
+        max_cost: u32,
+        edge_cost: impl Fn(&E) -> u32,
+    ) -> HashMap<S::Coordinate, u32>
+    where
+        S::Coordinate: Eq + Hash,
+    {
+        let mut costs = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        if let Some(i) = self.s.to_index(start) {
+            // the key is normalized by `from_index`, since `start` may be out of the range of a looping shape.
+            costs.insert(self.s.from_index(i), 0);
+            heap.push(Reverse((0u32, i)));
+        }
+        while let Some(Reverse((cost, i))) = heap.pop() {
+            let c = self.s.from_index(i);
+            if costs[&c] < cost {
+                continue;
+            }
+            for e in self.edges(c) {
+                let next = cost.saturating_add(edge_cost(e.weight()));
+                if next > max_cost {
+                    continue;
+                }
+                let t = match self.s.to_index(e.target()) {
+                    Some(t) => t,
+                    None => continue,
+                };
+                let target = self.s.from_index(t);
+                match costs.get(&target) {
+                    Some(c) if *c <= next => {}
+                    _ => {
+                        costs.insert(target, next);
+                        heap.push(Reverse((next, t)));
+                    }
+                }
+            }
+        }
+        costs
+    }
+
+    /// Iterate the ids of the edges connected to the node, which can be used in [`edge_weight`](`DataMap::edge_weight`).
+    /// The id of the edge to the backward direction is the one from the target node.
+    pub fn incident_edge_ids(
+        &self,
+        c: S::Coordinate,
+    ) -> impl Iterator<Item = (S::Coordinate, S::Axis)> + '_ {
+        self.edges(c).map(|e| {
+            let d = *e.direction();
+            if A::is_forward_direction(&d) {
+                (e.source(), A::from_direction(d))
+            } else {
+                (e.target(), A::from_direction(d))
+            }
+        })
+    }
+
+    /// Returns the weight of the edge connecting `a` and `b`, or [`None`] if they are not neighbors.
+    /// The edge is stored in the node it goes forward from, so this is symmetric for the undirected graph.
+    pub fn edge_weight_between(&self, a: S::Coordinate, b: S::Coordinate) -> Option<&E> {
+        let (source, axis) = self.edge_id_between(a, b)?;
+        self.edge_weight((source, axis))
+    }
+
+    /// Returns the mutable reference to the weight of the edge connecting `a` and `b`,
+    /// or [`None`] if they are not neighbors. See [`edge_weight_between`](`Self::edge_weight_between`).
+    pub fn edge_weight_mut_between(
+        &mut self,
+        a: S::Coordinate,
+        b: S::Coordinate,
+    ) -> Option<&mut E> {
+        let (source, axis) = self.edge_id_between(a, b)?;
+        self.edge_weight_mut((source, axis))
+    }
+
+    fn edge_id_between(&self, a: S::Coordinate, b: S::Coordinate) -> Option<(S::Coordinate, A)> {
+        // `move_coord` may succeed from the coordinate out of the graph.
+        self.s.to_offset(a).ok()?;
+        let d = self.s.get_direction(a, b)?;
+        let source = if A::is_forward_direction(&d) { a } else { b };
+        Some((source, A::from_direction(d)))
+    }
+
+    /// Returns the combinatorial Laplacian matrix as the `(row, column, value)` triplets (COO format),
+    /// indexed by [`to_index`](`Shape::to_index`).
+    /// The diagonal is the degree of the node and the off-diagonal is -1 for each edge.
+    /// See [`laplacian_with`](`Self::laplacian_with`) for the weighted one.
+    pub fn laplacian(&self) -> Vec<(usize, usize, f64)> {
+        self.laplacian_with(|_| 1.0)
+    }
+
+    /// Returns the Laplacian matrix as the `(row, column, value)` triplets (COO format),
+    /// indexed by [`to_index`](`Shape::to_index`), with the weight of each edge given by `weight`.
+    /// The diagonal is the sum of the weights of the edges from the node and the off-diagonal is the negated weight.
+    /// Each row is built from the outgoing edges, so it is the out-degree Laplacian if the graph is directed.
+    /// The triplets with the same position should be summed up, which happens when there are multiple edges between the nodes in a small loop.
+    pub fn laplacian_with(&self, mut weight: impl FnMut(&E) -> f64) -> Vec<(usize, usize, f64)> {
+        let mut triplets = Vec::new();
+        for i in 0..self.s.node_count() {
+            let diag = triplets.len();
+            triplets.push((i, i, 0.0));
+            let mut degree = 0.0;
+            self.for_each_edge(self.s.from_index(i), |e| {
+                if let Some(j) = self.s.to_index(e.target_id) {
+                    let w = weight(e.edge_weight);
+                    degree += w;
+                    triplets.push((i, j, -w));
+                }
+            });
+            triplets[diag].2 = degree;
+        }
+        triplets
+    }
+
+    /// Creates a [`StableGraph`](`petgraph::stable_graph::StableGraph`) with the same nodes and edges, copying the weights,
+    /// so that nodes and edges can be added or removed after.
+    /// The index of the node is same as [`to_index`](`Shape::to_index`).
+    /// The edge type is [`EdgeTypeWrap`] of the axis, so it is directed if and only if this graph is directed.
+    #[cfg(feature = "stable_graph")]
+    pub fn to_stable_graph(&self) -> petgraph::stable_graph::StableGraph<N, E, EdgeTypeWrap<A>>
+    where
+        N: Clone,
+        E: Clone,
+    {
+        use petgraph::{graph::node_index, stable_graph::StableGraph, visit::IntoEdgeReferences};
+        let mut g = StableGraph::with_capacity(self.node_count(), 0);
+        for n in self.nodes.ref_1d() {
+            g.add_node(n.clone());
+        }
+        for e in self.edge_references() {
+            let (s, t) = (self.s.to_index(e.source()), self.s.to_index(e.target()));
+            let (s, t) = unsafe {
+                (
+                    s.unwrap_or_else(|| unreachable_debug_checked()),
+                    t.unwrap_or_else(|| unreachable_debug_checked()),
+                )
+            };
+            g.add_edge(node_index(s), node_index(t), e.weight().clone());
+        }
+        g
+    }
+
+    /// Returns the diameter of the graph, the maximum cost of the shortest paths between all the pairs of nodes.
+    /// The pairs which are not reachable are ignored.
+    ///
+    /// This runs dijkstra from every node so it takes `O(V (V + E) log V)`.
+    /// Don't use it for large graphs.
+    pub fn diameter<F, K>(&self, mut edge_cost: F) -> K
+    where
+        F: FnMut(EdgeReference<'_, S::Coordinate, E, D, A>) -> K,
+        K: Measure + Copy,
+    {
+        let mut scratch = PathScratch::with_capacity(self.node_count());
+        let mut max = K::default();
+        for i in 0..self.node_count() {
+            let costs = dijkstra_reuse(&mut scratch, self, self.s.from_index(i), &mut edge_cost);
+            for c in costs.iter().flatten() {
+                if max < *c {
+                    max = *c;
+                }
+            }
+        }
+        max
+    }
+}
+
+impl<N, E, S, A> LatticeGraph<N, E, S>
+where
+    S: Shape<Axis = A>,
+    A: Axis<Direction = A> + Copy,
+{
+    /// Iterate the direction and the weight of each edge going out from the node in the directed graph.
+    /// The directions which can't move from the node are skipped, and the rest are yielded
+    /// in the order of the index of the axis, which is the same order as
+    /// [`edges_directed`](`petgraph::visit::IntoEdgesDirected::edges_directed`) with [`Outgoing`](`petgraph::Outgoing`).
+    pub fn out_edges(&self, c: S::Coordinate) -> impl Iterator<Item = (A, &E)> + '_ {
+        self.adjacencies(c).map(|(d, _, e)| (d, e))
+    }
 }
 
 impl<N, E, S: Shape + Default> LatticeGraph<N, E, S> {
@@ -155,6 +885,7 @@ impl<N, E, S: Shape> LatticeGraph<MaybeUninit<N>, MaybeUninit<E>, S> {
                 .map(|e| e.assume_init())
                 .collect(),
             s: core::ptr::read(&md.s),
+            connectivity: core::ptr::read(&md.connectivity),
         }
     }
 }
@@ -169,9 +900,13 @@ impl<N, E, S: Shape> Drop for LatticeGraph<N, E, S> {
             unsafe {
                 for (di, edges) in e.drain(..).enumerate() {
                     let dir = S::Axis::from_index_unchecked(di).foward();
-                    for (coord, mut e) in ni.clone().zip(edges.into_raw()) {
+                    let mut raw = edges.into_raw();
+                    let ptr = raw.as_mut_ptr();
+                    // set len to 0 not to drop the elements when dropping the vec.
+                    raw.set_len(0);
+                    for (i, coord) in ni.clone().enumerate() {
                         if s.move_coord(coord, dir.clone()).is_ok() {
-                            drop_in_place(&mut e);
+                            drop_in_place(ptr.add(i));
                         }
                     }
                 }
@@ -391,6 +1126,21 @@ impl<S: Shape> VisMap<S> {
         }
         Self { v: vec, s }
     }
+
+    /// Clear the map and resize it to the shape, since it may be created by the graph of another shape.
+    pub(crate) fn reset(&mut self, s: &S) {
+        let v = s.vertical();
+        self.v
+            .resize_with(s.horizontal(), || FixedBitSet::with_capacity(v));
+        for x in self.v.iter_mut() {
+            if x.len() == v {
+                x.clear();
+            } else {
+                *x = FixedBitSet::with_capacity(v);
+            }
+        }
+        self.s = s.clone();
+    }
 }
 
 impl<S: Shape> VisitMap<S::Coordinate> for VisMap<S> {
@@ -421,6 +1171,6 @@ impl<N, E, S: Shape + Clone> Visitable for LatticeGraph<N, E, S> {
     }
 
     fn reset_map(self: &Self, map: &mut Self::Map) {
-        map.v.iter_mut().for_each(|x| x.clear())
+        map.reset(&self.s)
     }
 }
@@ -1,6 +1,8 @@
 use std::iter::FusedIterator;
 
-use petgraph::visit::{GetAdjacencyMatrix, IntoNeighbors, IntoNeighborsDirected};
+use petgraph::visit::{
+    EdgeRef, GetAdjacencyMatrix, IntoEdgesDirected, IntoNeighbors, IntoNeighborsDirected,
+};
 
 use super::*;
 
@@ -9,15 +11,25 @@ use super::*;
 pub struct Neighbors<'a, N, E, S: Shape, C = <S as Shape>::Coordinate> {
     graph: &'a LatticeGraph<N, E, S>,
     node: C,
+    index: usize,
     state: usize,
 }
 
-impl<'a, N, E, S: Shape, C> Neighbors<'a, N, E, S, C> {
-    pub(crate) fn new(graph: &'a LatticeGraph<N, E, S>, node: C) -> Self {
+impl<'a, N, E, S: Shape> Neighbors<'a, N, E, S> {
+    pub(crate) fn new(graph: &'a LatticeGraph<N, E, S>, node: S::Coordinate) -> Self {
+        let (index, state) = if graph.has_connectivity_cache() {
+            match graph.s.to_index(node) {
+                Some(i) => (i, 0),
+                None => (0, S::Axis::UNDIRECTED_COUNT),
+            }
+        } else {
+            (0, 0)
+        };
         Self {
             graph,
             node,
-            state: 0,
+            index,
+            state,
         }
     }
 }
@@ -35,9 +47,11 @@ where
         while self.state < S::Axis::UNDIRECTED_COUNT {
             unsafe {
                 let d = D::dir_from_index_unchecked(self.state);
-                let n = self.graph.s.move_coord(self.node, d.clone());
+                let n = self
+                    .graph
+                    .move_coord_cached(self.node, self.index, d.clone());
                 self.state += 1;
-                if let Ok(target) = n {
+                if let Some(target) = n {
                     return Some(target);
                 }
             }
@@ -60,6 +74,89 @@ where
 {
 }
 
+/// Precomputed table of the neighbors of all nodes in the [`Shape`].
+/// Looking up the neighbors is a bit read and [`move_coord_unchecked`](`Shape::move_coord_unchecked`)
+/// instead of [`move_coord`](`Shape::move_coord`), which is faster for shapes with complex arithmetic like hex.
+/// It shares the bits with the connectivity cache of the graph. See [`LatticeGraph::neighbor_table`].
+#[derive(Debug, Clone)]
+pub struct NeighborTable<S: Shape> {
+    s: S,
+    connectivity: FixedBitSet,
+}
+
+impl<S: Shape> NeighborTable<S> {
+    /// Get a reference to the shape of the table.
+    pub fn shape(&self) -> &S {
+        &self.s
+    }
+
+    /// Returns the neighbor of the node to the direction.
+    pub fn get(
+        &self,
+        c: S::Coordinate,
+        dir: <S::Axis as Axis>::Direction,
+    ) -> Option<S::Coordinate> {
+        let i = self.s.to_index(c)?;
+        self.get_by_index(c, i, dir)
+    }
+
+    fn get_by_index(
+        &self,
+        c: S::Coordinate,
+        index: usize,
+        dir: <S::Axis as Axis>::Direction,
+    ) -> Option<S::Coordinate> {
+        if self
+            .connectivity
+            .contains(index * S::Axis::UNDIRECTED_COUNT + dir.dir_to_index())
+        {
+            Some(unsafe { self.s.move_coord_unchecked(c, dir) })
+        } else {
+            None
+        }
+    }
+
+    /// Iterate the neighbors of the node. It is same order as [`neighbors`](`IntoNeighbors::neighbors`).
+    pub fn neighbors(&self, c: S::Coordinate) -> impl Iterator<Item = S::Coordinate> + '_ {
+        match self.s.to_index(c) {
+            Some(i) => self.neighbors_inner(c, i, 0..S::Axis::UNDIRECTED_COUNT),
+            None => self.neighbors_inner(c, 0, 0..0),
+        }
+    }
+
+    /// Iterate the neighbors of the node with the index. See [`to_index`](`Shape::to_index`).
+    /// # Panics
+    /// Panics if the index is out of range.
+    pub fn neighbors_by_index(&self, index: usize) -> impl Iterator<Item = S::Coordinate> + '_ {
+        assert!(index < self.s.node_count(), "index out of range");
+        let c = self.s.from_index(index);
+        self.neighbors_inner(c, index, 0..S::Axis::UNDIRECTED_COUNT)
+    }
+
+    fn neighbors_inner(
+        &self,
+        c: S::Coordinate,
+        index: usize,
+        dirs: std::ops::Range<usize>,
+    ) -> impl Iterator<Item = S::Coordinate> + '_ {
+        dirs.filter_map(move |d| {
+            let dir = unsafe { <S::Axis as Axis>::Direction::dir_from_index_unchecked(d) };
+            self.get_by_index(c, index, dir)
+        })
+    }
+}
+
+impl<N, E, S: Shape + Clone> LatticeGraph<N, E, S> {
+    /// Creates a [`NeighborTable`] of this graph's shape.
+    /// It reuses the cache of [`with_connectivity_cache`](`Self::with_connectivity_cache`) if the graph has it.
+    pub fn neighbor_table(&self) -> NeighborTable<S> {
+        NeighborTable {
+            s: self.s.clone(),
+            connectivity: self.connectivity_bits(),
+        }
+    }
+}
+
 impl<'a, N, E, S, D> IntoNeighbors for &'a LatticeGraph<N, E, S>
 where
     S: Shape,
@@ -73,35 +170,84 @@ where
     }
 }
 
-impl<'a, N, E, S, D> IntoNeighborsDirected for &'a LatticeGraph<N, E, S>
+/// Neighbors of the node with [`Direction`](`petgraph::Direction`). See [`IntoNeighborsDirected`].
+/// It yields the targets of [`EdgesDirected`] for [`Outgoing`](`petgraph::Direction::Outgoing`)
+/// and the sources for [`Incoming`](`petgraph::Direction::Incoming`).
+#[derive(Debug)]
+pub struct NeighborsDirected<'a, N, E, S: Shape, C = <S as Shape>::Coordinate> {
+    edges: Edges<'a, N, E, S, C, petgraph::Direction>,
+    direction: petgraph::Direction,
+}
+
+impl<'a, N, E, S, C, D, A> Iterator for NeighborsDirected<'a, N, E, S, C>
 where
-    S: Shape,
-    S::Axis: Axis<Direction = D>,
-    D: AxisDirection + Clone,
+    C: Copy,
+    S: Shape<Coordinate = C, Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
 {
-    type NeighborsDirected = Neighbors<'a, N, E, S>;
+    type Item = C;
 
-    fn neighbors_directed(self: Self, a: Self::NodeId, _d: petgraph::Direction) -> Self::Neighbors {
-        Neighbors::new(self, a)
+    fn next(&mut self) -> Option<Self::Item> {
+        let e = self.edges.next()?;
+        Some(match self.direction {
+            petgraph::Direction::Outgoing => e.target(),
+            petgraph::Direction::Incoming => e.source(),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.edges.size_hint()
     }
 }
 
+impl<'a, N, E, S, C, D, A> FusedIterator for NeighborsDirected<'a, N, E, S, C>
+where
+    C: Copy,
+    S: Shape<Coordinate = C, Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+{
+}
+
+impl<'a, N, E, S, C, D, A> IntoNeighborsDirected for &'a LatticeGraph<N, E, S>
+where
+    C: Copy,
+    S: Shape<Coordinate = C, Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+{
+    type NeighborsDirected = NeighborsDirected<'a, N, E, S>;
+
+    fn neighbors_directed(
+        self,
+        a: Self::NodeId,
+        d: petgraph::Direction,
+    ) -> Self::NeighborsDirected {
+        NeighborsDirected {
+            edges: self.edges_directed(a, d),
+            direction: d,
+        }
+    }
+}
+
+/// The matrix has a bit for each node and direction, which is `node_count * UNDIRECTED_COUNT` bits
+/// instead of `node_count * node_count`, as the degree is bounded.
+/// If the graph has the connectivity cache, it is reused.
 impl<N, E, S, C> GetAdjacencyMatrix for LatticeGraph<N, E, S>
 where
     C: Copy + PartialEq,
     S: Shape<Coordinate = C>,
 {
-    type AdjMatrix = ();
-    fn adjacency_matrix(self: &Self) -> Self::AdjMatrix {
-        ()
+    type AdjMatrix = FixedBitSet;
+    fn adjacency_matrix(&self) -> Self::AdjMatrix {
+        self.connectivity_bits()
     }
 
-    fn is_adjacent(
-        self: &Self,
-        _matrix: &Self::AdjMatrix,
-        a: Self::NodeId,
-        b: Self::NodeId,
-    ) -> bool {
-        self.s.is_neighbor(a, b)
+    fn is_adjacent(&self, matrix: &Self::AdjMatrix, a: Self::NodeId, b: Self::NodeId) -> bool {
+        match (self.s.to_index(a), self.s.get_direction(a, b)) {
+            (Some(i), Some(d)) => matrix.contains(i * S::Axis::UNDIRECTED_COUNT + d.dir_to_index()),
+            _ => false,
+        }
     }
 }
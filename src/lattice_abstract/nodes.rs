@@ -48,6 +48,7 @@ impl<'a, N, E, S: Shape> IntoNodeIdentifiers for &'a LatticeGraph<N, E, S> {
 }
 
 /// Iterate all nodes of [`LatticeGraph`]. See [`IntoNodeReferences`].
+#[derive(Debug)]
 pub struct NodeReferences<'a, N, E, S: Shape> {
     graph: &'a LatticeGraph<N, E, S>,
     index: usize,
@@ -65,6 +66,20 @@ impl<'a, N, E, S: Shape> Iterator for NodeReferences<'a, N, E, S> {
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.graph.s.node_count() - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<'a, N, E, S: Shape> Clone for NodeReferences<'a, N, E, S> {
+    fn clone(&self) -> Self {
+        Self {
+            graph: self.graph,
+            index: self.index,
+        }
+    }
 }
 
 impl<'a, N, E, S: Shape> FusedIterator for NodeReferences<'a, N, E, S> {}
@@ -84,6 +99,27 @@ impl<'a, N, E, S: Shape> IntoNodeReferences for &'a LatticeGraph<N, E, S> {
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<N, E, S: Shape> LatticeGraph<N, E, S> {
+    /// Parallel version of [`node_references`](`IntoNodeReferences::node_references`), in the same order.
+    pub fn par_node_references(
+        &self,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = (S::Coordinate, &N)>
+    where
+        N: Sync,
+        S: Sync,
+        S::Coordinate: Send,
+    {
+        use rayon::prelude::*;
+        let s = &self.s;
+        self.nodes
+            .ref_1d()
+            .par_iter()
+            .enumerate()
+            .map(move |(i, n)| (s.from_index(i), n))
+    }
+}
+
 impl<N, E, S: Shape> NodeCount for LatticeGraph<N, E, S> {
     fn node_count(self: &Self) -> usize {
         self.s.node_count()
@@ -0,0 +1,404 @@
+use std::{
+    collections::{hash_map, HashMap},
+    hash::Hash,
+    iter::{Enumerate, FusedIterator},
+    slice,
+};
+
+use petgraph::visit::{EdgeRef, IntoEdgeReferences, IntoEdges, IntoNeighbors};
+
+use super::*;
+
+/// [`LatticeGraph`] with extra edges like portals which connect the distant nodes.
+///
+/// The portal edges are directed, so add the both directions to make it two-way.
+/// It implements [`IntoNeighbors`] and [`IntoEdges`] yielding both of the lattice edges and the portal edges,
+/// so the path finding algorithms like [`dijkstra`](`petgraph::algo::dijkstra`) can go through the portals.
+#[derive(Debug)]
+pub struct PortalLattice<'a, N, E, S: Shape> {
+    graph: &'a LatticeGraph<N, E, S>,
+    portals: HashMap<S::Coordinate, Vec<(S::Coordinate, E)>>,
+}
+
+/// Id of the edge of [`PortalLattice`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PortalEdgeId<C, A> {
+    /// Edge of the lattice.
+    Lattice((C, A)),
+    /// Portal edge. It holds the source and the index of the portal from the source.
+    Portal(C, usize),
+}
+
+impl<'a, N, E, S> PortalLattice<'a, N, E, S>
+where
+    S: Shape,
+    S::Coordinate: Eq + Hash,
+{
+    /// Creates a graph without portals.
+    pub fn new(graph: &'a LatticeGraph<N, E, S>) -> Self {
+        Self {
+            graph,
+            portals: HashMap::new(),
+        }
+    }
+
+    /// Creates a graph with the portals.
+    pub fn with_portals(
+        graph: &'a LatticeGraph<N, E, S>,
+        portals: HashMap<S::Coordinate, Vec<(S::Coordinate, E)>>,
+    ) -> Self {
+        Self { graph, portals }
+    }
+
+    /// Add a portal edge from `source` to `target`.
+    /// Returns `None` and doesn't add it if `source` or `target` is not in the graph.
+    pub fn add_portal(
+        &mut self,
+        source: S::Coordinate,
+        target: S::Coordinate,
+        weight: E,
+    ) -> Option<PortalEdgeId<S::Coordinate, S::Axis>> {
+        let s = self.graph.shape();
+        if s.to_offset(source).is_err() || s.to_offset(target).is_err() {
+            return None;
+        }
+        let v = self.portals.entry(source).or_default();
+        v.push((target, weight));
+        Some(PortalEdgeId::Portal(source, v.len() - 1))
+    }
+
+    /// Get the lattice graph.
+    pub fn graph(&self) -> &'a LatticeGraph<N, E, S> {
+        self.graph
+    }
+
+    /// Get the portals from the node.
+    pub fn portals(&self, source: S::Coordinate) -> &[(S::Coordinate, E)] {
+        self.portals.get(&source).map_or(&[], |v| &v[..])
+    }
+}
+
+impl<'a, N, E, S: Shape> GraphBase for PortalLattice<'a, N, E, S> {
+    type NodeId = S::Coordinate;
+    type EdgeId = PortalEdgeId<S::Coordinate, S::Axis>;
+}
+
+impl<'a, N, E, S: Shape> Data for PortalLattice<'a, N, E, S> {
+    type NodeWeight = N;
+    type EdgeWeight = E;
+}
+
+impl<'a, N, E, S> DataMap for PortalLattice<'a, N, E, S>
+where
+    S: Shape,
+    S::Coordinate: Eq + Hash,
+{
+    fn node_weight(&self, id: Self::NodeId) -> Option<&Self::NodeWeight> {
+        self.graph.node_weight(id)
+    }
+
+    fn edge_weight(&self, id: Self::EdgeId) -> Option<&Self::EdgeWeight> {
+        match id {
+            PortalEdgeId::Lattice(id) => self.graph.edge_weight(id),
+            PortalEdgeId::Portal(c, i) => self.portals(c).get(i).map(|(_, e)| e),
+        }
+    }
+}
+
+impl<'a, N, E, S: Shape + Clone> Visitable for PortalLattice<'a, N, E, S> {
+    type Map = VisMap<S>;
+
+    fn visit_map(&self) -> Self::Map {
+        self.graph.visit_map()
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        self.graph.reset_map(map)
+    }
+}
+
+impl<'a, N, E, S: Shape> NodeCount for PortalLattice<'a, N, E, S> {
+    fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+}
+
+/// Neighbors of the node in [`PortalLattice`]. See [`IntoNeighbors`].
+pub struct PortalNeighbors<'b, N, E, S: Shape> {
+    lattice: Neighbors<'b, N, E, S>,
+    portals: slice::Iter<'b, (S::Coordinate, E)>,
+}
+
+impl<'b, N, E, S, D> Iterator for PortalNeighbors<'b, N, E, S>
+where
+    S: Shape,
+    S::Axis: Axis<Direction = D>,
+    D: AxisDirection + Clone,
+{
+    type Item = S::Coordinate;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lattice
+            .next()
+            .or_else(|| self.portals.next().map(|(c, _)| *c))
+    }
+}
+
+impl<'b, N, E, S, D> FusedIterator for PortalNeighbors<'b, N, E, S>
+where
+    S: Shape,
+    S::Axis: Axis<Direction = D>,
+    D: AxisDirection + Clone,
+{
+}
+
+impl<'a, 'b, N, E, S, D> IntoNeighbors for &'b PortalLattice<'a, N, E, S>
+where
+    S: Shape,
+    S::Coordinate: Eq + Hash,
+    S::Axis: Axis<Direction = D>,
+    D: AxisDirection + Clone,
+{
+    type Neighbors = PortalNeighbors<'b, N, E, S>;
+
+    fn neighbors(self, a: Self::NodeId) -> Self::Neighbors {
+        PortalNeighbors {
+            lattice: self.graph.neighbors(a),
+            portals: self.portals(a).iter(),
+        }
+    }
+}
+
+/// Edge reference of [`PortalLattice`]. See [`IntoEdges`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum PortalEdgeReference<'b, C, E, D, A> {
+    /// Edge of the lattice.
+    Lattice(EdgeReference<'b, C, E, D, A>),
+    /// Portal edge.
+    Portal {
+        /// Source of the portal.
+        source: C,
+        /// Target of the portal.
+        target: C,
+        /// Index of the portal from the source.
+        index: usize,
+        /// Weight of the portal.
+        weight: &'b E,
+    },
+}
+
+impl<'b, C: Clone, E, D: Clone, A> Clone for PortalEdgeReference<'b, C, E, D, A> {
+    fn clone(&self) -> Self {
+        match self {
+            PortalEdgeReference::Lattice(e) => PortalEdgeReference::Lattice(e.clone()),
+            PortalEdgeReference::Portal {
+                source,
+                target,
+                index,
+                weight,
+            } => PortalEdgeReference::Portal {
+                source: source.clone(),
+                target: target.clone(),
+                index: *index,
+                weight,
+            },
+        }
+    }
+}
+
+impl<'b, C: Copy, E, D: Copy, A> Copy for PortalEdgeReference<'b, C, E, D, A> {}
+
+impl<'b, C, E, D, A> EdgeRef for PortalEdgeReference<'b, C, E, D, A>
+where
+    C: Copy,
+    D: AxisDirection + Copy,
+    A: Axis<Direction = D>,
+{
+    type NodeId = C;
+    type EdgeId = PortalEdgeId<C, A>;
+    type Weight = E;
+
+    fn source(&self) -> Self::NodeId {
+        match self {
+            PortalEdgeReference::Lattice(e) => e.source(),
+            PortalEdgeReference::Portal { source, .. } => *source,
+        }
+    }
+
+    fn target(&self) -> Self::NodeId {
+        match self {
+            PortalEdgeReference::Lattice(e) => e.target(),
+            PortalEdgeReference::Portal { target, .. } => *target,
+        }
+    }
+
+    fn weight(&self) -> &Self::Weight {
+        match self {
+            PortalEdgeReference::Lattice(e) => e.edge_weight,
+            PortalEdgeReference::Portal { weight, .. } => weight,
+        }
+    }
+
+    fn id(&self) -> Self::EdgeId {
+        match self {
+            PortalEdgeReference::Lattice(e) => PortalEdgeId::Lattice(e.id()),
+            PortalEdgeReference::Portal { source, index, .. } => {
+                PortalEdgeId::Portal(*source, *index)
+            }
+        }
+    }
+}
+
+/// Edges of the node in [`PortalLattice`]. See [`IntoEdges`].
+pub struct PortalEdges<'b, N, E, S: Shape> {
+    lattice: Edges<'b, N, E, S>,
+    source: S::Coordinate,
+    portals: Enumerate<slice::Iter<'b, (S::Coordinate, E)>>,
+}
+
+impl<'b, N, E, S, C, D, A> Iterator for PortalEdges<'b, N, E, S>
+where
+    C: Copy,
+    S: Shape<Coordinate = C, Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+{
+    type Item = PortalEdgeReference<'b, C, E, D, A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.lattice.next() {
+            return Some(PortalEdgeReference::Lattice(e));
+        }
+        let source = self.source;
+        self.portals
+            .next()
+            .map(|(index, (target, weight))| PortalEdgeReference::Portal {
+                source,
+                target: *target,
+                index,
+                weight,
+            })
+    }
+}
+
+impl<'b, N, E, S, C, D, A> FusedIterator for PortalEdges<'b, N, E, S>
+where
+    C: Copy,
+    S: Shape<Coordinate = C, Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+{
+}
+
+impl<'a, 'b, N, E, S, C, D, A> IntoEdges for &'b PortalLattice<'a, N, E, S>
+where
+    C: Copy + Eq + Hash + 'b,
+    S: Shape<Coordinate = C, Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+{
+    type Edges = PortalEdges<'b, N, E, S>;
+
+    fn edges(self, a: Self::NodeId) -> Self::Edges {
+        PortalEdges {
+            lattice: self.graph.edges(a),
+            source: a,
+            portals: self.portals(a).iter().enumerate(),
+        }
+    }
+}
+
+type PortalsFrom<'b, C, E> = (C, Enumerate<slice::Iter<'b, (C, E)>>);
+
+/// Iterator for all edges of [`PortalLattice`]. See [`IntoEdgeReferences`].
+pub struct PortalEdgeReferences<'b, N, E, S: Shape> {
+    lattice: EdgeReferences<'b, N, E, S>,
+    portals: hash_map::Iter<'b, S::Coordinate, Vec<(S::Coordinate, E)>>,
+    current: Option<PortalsFrom<'b, S::Coordinate, E>>,
+}
+
+impl<'b, N, E, S, C, D, A> Iterator for PortalEdgeReferences<'b, N, E, S>
+where
+    C: Copy,
+    S: Shape<Coordinate = C, Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+{
+    type Item = PortalEdgeReference<'b, C, E, D, A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.lattice.next() {
+            return Some(PortalEdgeReference::Lattice(e));
+        }
+        loop {
+            if let Some((source, iter)) = &mut self.current {
+                if let Some((index, (target, weight))) = iter.next() {
+                    return Some(PortalEdgeReference::Portal {
+                        source: *source,
+                        target: *target,
+                        index,
+                        weight,
+                    });
+                }
+            }
+            let (source, v) = self.portals.next()?;
+            self.current = Some((*source, v.iter().enumerate()));
+        }
+    }
+}
+
+impl<'a, 'b, N, E, S, C, D, A> IntoEdgeReferences for &'b PortalLattice<'a, N, E, S>
+where
+    C: Copy + Eq + Hash + 'b,
+    S: Shape<Coordinate = C, Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+{
+    type EdgeRef = PortalEdgeReference<'b, C, E, D, A>;
+    type EdgeReferences = PortalEdgeReferences<'b, N, E, S>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        PortalEdgeReferences {
+            lattice: self.graph.edge_references(),
+            portals: self.portals.iter(),
+            current: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice_abstract::square::{SquareGraphAbstract, SquareOffset, SquareShape};
+    use petgraph::algo::dijkstra;
+
+    #[test]
+    fn portal_shortcut() {
+        let graph =
+            SquareGraphAbstract::<(), u32>::new_with(SquareShape::new(10, 2), |_| (), |_, _| 1);
+        let start = SquareOffset::from((0, 0));
+        let goal = SquareOffset::from((9, 1));
+        let mut portal = PortalLattice::new(&graph);
+        assert_eq!(
+            dijkstra(&portal, start, Some(goal), |e| *e.weight())[&goal],
+            10
+        );
+
+        let id = portal.add_portal((1, 0).into(), (9, 0).into(), 2);
+        assert_eq!(id, Some(PortalEdgeId::Portal((1, 0).into(), 0)));
+        assert_eq!(portal.edge_weight(id.unwrap()), Some(&2));
+        assert_eq!(portal.add_portal((10, 0).into(), (9, 0).into(), 2), None);
+        assert!(portal
+            .neighbors((1, 0).into())
+            .any(|c| c == SquareOffset::from((9, 0))));
+        // Portals are one way.
+        assert!(!portal
+            .neighbors((9, 0).into())
+            .any(|c| c == SquareOffset::from((1, 0))));
+
+        let costs = dijkstra(&portal, start, Some(goal), |e| *e.weight());
+        assert_eq!(costs[&goal], 4);
+        assert_eq!(costs[&SquareOffset::from((9, 0))], 3);
+        assert_eq!(portal.edge_references().count(), 9 * 2 + 10 + 1);
+    }
+}
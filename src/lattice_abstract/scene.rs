@@ -0,0 +1,97 @@
+use super::*;
+use petgraph::visit::IntoEdgeReferences;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct SceneNode<'a, N> {
+    id: usize,
+    x: f32,
+    y: f32,
+    weight: &'a N,
+}
+
+#[derive(Serialize)]
+struct SceneEdge<'a, E> {
+    source: usize,
+    target: usize,
+    weight: &'a E,
+}
+
+#[derive(Serialize)]
+struct Scene<'a, N, E> {
+    nodes: Vec<SceneNode<'a, N>>,
+    edges: Vec<SceneEdge<'a, E>>,
+}
+
+impl<N, E, S, A, D> LatticeGraph<N, E, S>
+where
+    S: Shape<Axis = A>,
+    A: Axis<Direction = D>,
+    D: AxisDirection + Copy,
+    N: Serialize,
+    E: Serialize,
+{
+    /// Export the nodes and edges as a json for visualization.
+    ///
+    /// The output is an object with `nodes` of `{id, x, y, weight}` and `edges` of `{source, target, weight}`.
+    /// `id` is the index of [`to_index`](`Shape::to_index`) and the position is
+    /// the [`Offset`] of the node multiplied by `tile_size`.
+    pub fn to_scene_json(&self, tile_size: f32) -> String {
+        let nodes = (0..self.node_count())
+            .map(|i| {
+                let o = self.s.index_to_offset(i);
+                SceneNode {
+                    id: i,
+                    x: o.horizontal as f32 * tile_size,
+                    y: o.vertical as f32 * tile_size,
+                    weight: unsafe { self.nodes.ref_1d().get_unchecked(i) },
+                }
+            })
+            .collect();
+        let edges = self
+            .edge_references()
+            .map(|e| unsafe {
+                SceneEdge {
+                    source: self
+                        .s
+                        .to_index(e.source())
+                        .unwrap_or_else(|| unreachable_debug_checked()),
+                    target: self
+                        .s
+                        .to_index(e.target())
+                        .unwrap_or_else(|| unreachable_debug_checked()),
+                    weight: e.edge_weight,
+                }
+            })
+            .collect();
+        serde_json::to_string(&Scene { nodes, edges }).expect("serializing scene failed")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lattice_abstract::{
+        shapes::Axis,
+        square::{SquareGraphAbstract, SquareShape},
+    };
+    use serde_json::Value;
+
+    #[test]
+    fn to_scene_json() {
+        let graph = SquareGraphAbstract::<usize, u32>::new_with(
+            SquareShape::new(3, 2),
+            |c| c.0.horizontal * 10 + c.0.vertical,
+            |_, a| a.to_index() as u32,
+        );
+        let json = graph.to_scene_json(2.0);
+        let v: Value = serde_json::from_str(&json).unwrap();
+        let nodes = v["nodes"].as_array().unwrap();
+        let edges = v["edges"].as_array().unwrap();
+        assert_eq!(nodes.len(), 6);
+        assert_eq!(edges.len(), 2 * 2 + 3);
+        let n = nodes.iter().find(|n| n["id"] == 3).unwrap();
+        assert_eq!(n["x"], 2.0);
+        assert_eq!(n["y"], 2.0);
+        assert_eq!(n["weight"], 11);
+    }
+}
@@ -0,0 +1,283 @@
+use super::*;
+use serde::{
+    de::Error as _,
+    ser::{SerializeSeq, SerializeStruct},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+/// Edge array of an axis. The slots of the edges which don't exist are serialized as `None`,
+/// so it is deserialized as [`FixedVec2D`]`<Option<E>>`.
+struct EdgeArray<'a, N, E, S: Shape> {
+    graph: &'a LatticeGraph<N, E, S>,
+    axis: usize,
+}
+
+impl<'a, N, E: Serialize, S: Shape> Serialize for EdgeArray<'a, N, E, S> {
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        let mut st = serializer.serialize_struct("FixedVec2D", 3)?;
+        let edges = unsafe { self.graph.edges.get_unchecked(self.axis) };
+        st.serialize_field("h_size", &edges.h_size())?;
+        st.serialize_field("v_size", &edges.v_size())?;
+        st.serialize_field("data", &EdgeData(self))?;
+        st.end()
+    }
+}
+
+struct EdgeData<'a, 'b, N, E, S: Shape>(&'b EdgeArray<'a, N, E, S>);
+
+impl<'a, 'b, N, E: Serialize, S: Shape> Serialize for EdgeData<'a, 'b, N, E, S> {
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        let EdgeArray { graph, axis } = self.0;
+        let s = &graph.s;
+        let dir = unsafe { S::Axis::from_index_unchecked(*axis) }.foward();
+        let edges = unsafe { graph.edges.get_unchecked(*axis) };
+        let mut seq = serializer.serialize_seq(Some(edges.size()))?;
+        for (h, es) in edges.ref_2d().iter().enumerate() {
+            for (v, e) in es.iter().enumerate() {
+                let c = s.from_offset(Offset::new(h, v));
+                if s.move_coord(c, dir.clone()).is_ok() {
+                    seq.serialize_element(&Some(e))?;
+                } else {
+                    seq.serialize_element(&None::<&E>)?;
+                }
+            }
+        }
+        seq.end()
+    }
+}
+
+struct EdgeArrays<'a, N, E, S: Shape>(&'a LatticeGraph<N, E, S>);
+
+impl<'a, N, E: Serialize, S: Shape> Serialize for EdgeArrays<'a, N, E, S> {
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        serializer.collect_seq((0..self.0.edges.len()).map(|axis| EdgeArray {
+            graph: self.0,
+            axis,
+        }))
+    }
+}
+
+/// The absent edges near the border are serialized as `null` (or `None`).
+impl<N, E, S> Serialize for LatticeGraph<N, E, S>
+where
+    N: Serialize,
+    E: Serialize,
+    S: Shape + Serialize,
+{
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        let mut st = serializer.serialize_struct("LatticeGraph", 3)?;
+        st.serialize_field("shape", &self.s)?;
+        st.serialize_field("nodes", &self.nodes)?;
+        st.serialize_field("edges", &EdgeArrays(self))?;
+        st.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "LatticeGraph")]
+struct RawGraph<N, E, S> {
+    shape: S,
+    nodes: FixedVec2D<N>,
+    edges: Vec<FixedVec2D<Option<E>>>,
+}
+
+/// The edges which exist must be `Some`, and the values of the absent edges are ignored.
+impl<'de, N, E, S> Deserialize<'de> for LatticeGraph<N, E, S>
+where
+    N: Deserialize<'de>,
+    E: Deserialize<'de>,
+    S: Shape + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RawGraph {
+            shape,
+            nodes,
+            edges,
+        } = RawGraph::<N, E, S>::deserialize(deserializer)?;
+        Self::check_shape(&shape).map_err(D::Error::custom)?;
+        let (h, v) = (shape.horizontal(), shape.vertical());
+        // SAFETY : `check_shape` rejects the horizontal size 0.
+        let h_nz = unsafe { NonZeroUsize::new_unchecked(h) };
+        if nodes.h_size() != h || nodes.v_size() != v {
+            return Err(D::Error::custom("size of nodes doesn't match the shape"));
+        }
+        if edges.len() != S::Axis::COUNT {
+            return Err(D::Error::invalid_length(
+                edges.len(),
+                &"the count of the axis",
+            ));
+        }
+        for (i, es) in edges.iter().enumerate() {
+            if es.h_size() != h || es.v_size() != v {
+                return Err(D::Error::custom("size of edges doesn't match the shape"));
+            }
+            let dir = unsafe { S::Axis::from_index_unchecked(i) }.foward();
+            for (o, e) in es.ref_1d().iter().enumerate() {
+                let c = shape.from_offset(Offset::new(o / v, o % v));
+                if e.is_none() && shape.move_coord(c, dir.clone()).is_ok() {
+                    return Err(D::Error::custom("missing the weight of an edge"));
+                }
+            }
+        }
+        let edges = edges
+            .into_iter()
+            .map(|es| unsafe {
+                let mut new = FixedVec2D::<MaybeUninit<E>>::new_uninit(h_nz, v);
+                for (n, e) in new.mut_1d().iter_mut().zip(es.into_raw()) {
+                    if let Some(e) = e {
+                        *n = MaybeUninit::new(e);
+                    }
+                }
+                new.assume_init()
+            })
+            .collect();
+        // SAFETY : the edges which exist are initialized.
+        Ok(unsafe { Self::new_raw(nodes, edges, shape) })
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<N, E, S> LatticeGraph<N, E, S>
+where
+    N: Serialize,
+    E: Serialize,
+    S: Shape + Serialize,
+{
+    /// Serialize the graph to the compact binary format with [`bincode`], for fast save and load of large maps.
+    /// The layout is same as the [`Serialize`] impl.
+    ///
+    /// # Panics
+    /// Panics if the serialization of the weights or the shape fails.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("failed to serialize the graph")
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl<N, E, S> LatticeGraph<N, E, S>
+where
+    N: serde::de::DeserializeOwned,
+    E: serde::de::DeserializeOwned,
+    S: Shape + serde::de::DeserializeOwned,
+{
+    /// Deserialize the graph from the bytes made by [`to_bytes`](`Self::to_bytes`).
+    /// The sizes in the bytes are checked, and it never reads more than `bytes.len()` bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        use bincode::Options;
+        // same encoding as `bincode::serialize` used in `to_bytes`.
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+            .with_limit(bytes.len() as u64)
+            .deserialize(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        hex::axial_based::*,
+        lattice_abstract::{
+            square::{SquareGraphAbstract, SquareShape},
+            Shape,
+        },
+    };
+    use petgraph::Undirected;
+    use petgraph::{data::DataMap, visit::*};
+
+    #[test]
+    fn hex_json_roundtrip() {
+        type G = HexGraphConst<HexAxial, String, OddR, 5, 5>;
+        let graph = G::new_with_s(|c| c, |c, a| format!("{:?}{:?}", c, a));
+        let json = serde_json::to_string(&graph).unwrap();
+        let de: G = serde_json::from_str(&json).unwrap();
+        for e in graph.edge_references() {
+            assert_eq!(de.edge_weight(e.id()), Some(e.weight()));
+        }
+        for c in graph.node_identifiers() {
+            assert_eq!(de.node_weight(c), Some(&c));
+        }
+        // absent edges are serialized as null.
+        let v: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(v["edges"][0]["data"][4].is_null());
+        assert_eq!(v["edges"][0]["data"].as_array().unwrap().len(), 25);
+    }
+
+    fn same_graph(a: &SquareGraphAbstract<u8, u8>, b: &SquareGraphAbstract<u8, u8>) -> bool {
+        a.same_shape_as(b)
+            && a.edge_references().eq(b.edge_references())
+            && a.node_references().eq(b.node_references())
+    }
+
+    #[test]
+    fn missing_edge() {
+        let graph =
+            SquareGraphAbstract::<u8, u8>::new_with(SquareShape::new(2, 2), |_| 1, |_, _| 2);
+        let json = serde_json::to_string(&graph).unwrap();
+        let de: SquareGraphAbstract<u8, u8> = serde_json::from_str(&json).unwrap();
+        assert!(same_graph(&de, &graph));
+        let mut v: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(v["edges"][0]["data"][2].is_null());
+        // absent slot with value is ignored.
+        v["edges"][0]["data"][2] = 5.into();
+        let de: SquareGraphAbstract<u8, u8> = serde_json::from_value(v.clone()).unwrap();
+        assert!(same_graph(&de, &graph));
+        // existing edge without value is error.
+        v["edges"][0]["data"][0] = serde_json::Value::Null;
+        assert!(serde_json::from_value::<SquareGraphAbstract<u8, u8>>(v.clone()).is_err());
+        v["shape"]["h"] = 3.into();
+        assert!(serde_json::from_value::<SquareGraphAbstract<u8, u8>>(v).is_err());
+    }
+
+    #[test]
+    fn overflow_shape() {
+        let v = usize::MAX / 2 + 1;
+        let json = format!(
+            r#"{{"shape":{{"h":2,"v":{v},"e":null}},"nodes":{{"h_size":2,"v_size":{v},"data":[]}},"edges":[]}}"#,
+            v = v
+        );
+        assert!(serde_json::from_str::<SquareGraphAbstract<u8, u8>>(&json).is_err());
+        let json = r#"{"shape":{"h":0,"v":2,"e":null},"nodes":{"h_size":1,"v_size":0,"data":[]},"edges":[]}"#;
+        assert!(serde_json::from_str::<SquareGraphAbstract<u8, u8>>(json).is_err());
+    }
+
+    #[test]
+    fn compact_shape_limit() {
+        type Compact = SquareShape<Undirected, u8>;
+        let shape: Compact = serde_json::from_str(r#"{"h":256,"v":3,"e":null}"#).unwrap();
+        assert_eq!((shape.horizontal(), shape.vertical()), (256, 3));
+        let json = serde_json::to_string(&SquareShape::<Undirected>::new(300, 3)).unwrap();
+        assert!(serde_json::from_str::<Compact>(&json).is_err());
+        assert!(serde_json::from_str::<SquareShape>(&json).is_ok());
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn bytes_roundtrip() {
+        let graph = SquareGraphAbstract::<u8, u8>::new_with(
+            SquareShape::new(100, 100),
+            |c| (c.0.horizontal * 3 + c.0.vertical) as u8,
+            |c, a| (c.0.horizontal + c.0.vertical * 7 + a as usize) as u8,
+        );
+        let bytes = graph.to_bytes();
+        // nodes and edge slots with the option tags and a few headers.
+        assert!(bytes.len() < 100 * 100 * 5 + 100);
+        let de = SquareGraphAbstract::<u8, u8>::from_bytes(&bytes).unwrap();
+        assert!(same_graph(&de, &graph));
+        assert!(SquareGraphAbstract::<u8, u8>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn bytes_overflow() {
+        // shape, nodes and edges of 2 x 2^(bits - 1) without any weight.
+        let (h, v) = (2usize, usize::MAX / 2 + 1);
+        let bytes = bincode::serialize(&(
+            (h, v, ()),
+            (h, v, Vec::<u8>::new()),
+            vec![(h, v, Vec::<Option<u8>>::new()); 2],
+        ))
+        .unwrap();
+        assert!(SquareGraphAbstract::<u8, u8>::from_bytes(&bytes).is_err());
+    }
+}
@@ -38,9 +38,19 @@ pub trait Shape: Clone {
     fn from_offset(&self, offset: Offset) -> Self::Coordinate;
 
     /// Convert coordinate from index.
+    /// The index is not checked, so the index out of [`node_count`](`Shape::node_count`) returns
+    /// a coordinate which may not be in the shape. Use [`try_from_index`](`Shape::try_from_index`) to check it.
     fn from_index(&self, index: usize) -> Self::Coordinate {
         self.from_offset(self.index_to_offset(index))
     }
+    /// Convert coordinate from index. Returns `None` if the index is out of [`node_count`](`Shape::node_count`).
+    fn try_from_index(&self, index: usize) -> Option<Self::Coordinate> {
+        if index < self.node_count() {
+            Some(self.from_index(index))
+        } else {
+            None
+        }
+    }
     /// Covert coordinate to index.
     fn to_index(&self, coord: Self::Coordinate) -> Option<usize> {
         let offset = self.to_offset(coord);
@@ -86,6 +96,16 @@ pub trait Shape: Clone {
         self.move_coord(coord, dir)
             .unwrap_or_else(|_| unreachable_debug_checked())
     }
+    /// Get the coordinate which moves to `coord` in the direction, that is the source of the incoming edge.
+    /// It is same as moving to the reversed direction, unless some directions can't be moved
+    /// like [`PartiallyDirected`](`crate::hex::shapes::PartiallyDirected`).
+    fn move_coord_reverse(
+        &self,
+        coord: Self::Coordinate,
+        dir: <Self::Axis as Axis>::Direction,
+    ) -> Result<Self::Coordinate, Self::CoordinateMoveError> {
+        self.move_coord(coord, reverse_direction::<Self::Axis>(dir))
+    }
     ///Check whether two coordinate is in neighbor.
     fn is_neighbor(&self, a: Self::Coordinate, b: Self::Coordinate) -> bool {
         self.get_direction(a, b).is_some()
@@ -109,6 +129,20 @@ pub trait Shape: Clone {
         }
         None
     }
+    /// Iterate the directions which can move from the coordinate, in the order of the index of the direction.
+    /// It is empty if the coordinate is not in the shape.
+    fn valid_directions(&self, coord: Self::Coordinate) -> ValidDirections<'_, Self> {
+        let index = if self.to_offset(coord).is_ok() {
+            0
+        } else {
+            <Self::Axis as Axis>::UNDIRECTED_COUNT
+        };
+        ValidDirections {
+            shape: self,
+            coord,
+            index,
+        }
+    }
 }
 
 impl<S: Shape> Shape for &S {
@@ -161,6 +195,10 @@ impl<S: Shape> Shape for &S {
         (*self).from_index(index)
     }
 
+    fn try_from_index(&self, index: usize) -> Option<Self::Coordinate> {
+        (*self).try_from_index(index)
+    }
+
     fn to_index(&self, coord: Self::Coordinate) -> Option<usize> {
         (*self).to_index(coord)
     }
@@ -190,6 +228,14 @@ impl<S: Shape> Shape for &S {
     {
         (*self).get_direction(source, target)
     }
+
+    fn move_coord_reverse(
+        &self,
+        coord: Self::Coordinate,
+        dir: <Self::Axis as Axis>::Direction,
+    ) -> Result<Self::Coordinate, Self::CoordinateMoveError> {
+        (*self).move_coord_reverse(coord, dir)
+    }
 }
 
 /// Axis of the graph. It holds what direction of edge which node has.
@@ -230,6 +276,90 @@ pub trait Axis: Copy + PartialEq {
     fn from_direction(dir: Self::Direction) -> Self;
 }
 
+/// Reverse the direction.
+pub(crate) fn reverse_direction<A: Axis>(dir: A::Direction) -> A::Direction {
+    let a = A::from_direction(dir.clone());
+    if A::is_forward_direction(&dir) {
+        a.backward()
+    } else {
+        a.foward()
+    }
+}
+
+/// Names of the [`Axis`] for tooling or debugging.
+pub trait AxisNames: Axis {
+    /// Names of the axis in the order of [`to_index`](`Axis::to_index`).
+    const NAMES: &'static [&'static str];
+    /// Returns the name of the axis.
+    fn name(&self) -> &'static str {
+        Self::NAMES[self.to_index()]
+    }
+}
+
+/**
+Compile-time size of the [`Shape`] with const size, like [`ConstHexAxialShape`](`crate::hex::axial_based::ConstHexAxialShape`).
+It can be used where a const is required, like the length of an array.
+```
+# use lattice_graph::hex::{axial_based::ConstHexAxialShape, shapes::OddR};
+# use lattice_graph::lattice_abstract::shapes::ConstShapeDims;
+type S = ConstHexAxialShape<OddR, (), 5, 3>;
+let array = [0u8; S::NODE_COUNT];
+assert_eq!(array.len(), 15);
+```
+*/
+pub trait ConstShapeDims: Shape {
+    /// Horizontal node count.
+    const H: usize;
+    /// Vertical node count.
+    const V: usize;
+    /// Node count.
+    const NODE_COUNT: usize = Self::H * Self::V;
+}
+
+/// Distance between two coordinates. It is implemented for closures `Fn(C, C) -> K`.
+pub trait Metric<C> {
+    /// Type of the distance.
+    type Distance: Ord;
+    /// Returns the distance between `a` and `b`.
+    fn distance(&self, a: C, b: C) -> Self::Distance;
+}
+
+impl<C, K: Ord, F: Fn(C, C) -> K> Metric<C> for F {
+    type Distance = K;
+    fn distance(&self, a: C, b: C) -> K {
+        self(a, b)
+    }
+}
+
+/// Iterator of the directions which can move from the coordinate. See [`Shape::valid_directions`].
+#[derive(Debug, Clone)]
+pub struct ValidDirections<'a, S, C = <S as Shape>::Coordinate> {
+    shape: &'a S,
+    coord: C,
+    index: usize,
+}
+
+impl<'a, S: Shape> Iterator for ValidDirections<'a, S> {
+    type Item = <S::Axis as Axis>::Direction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < <S::Axis as Axis>::UNDIRECTED_COUNT {
+            let d = unsafe { <S::Axis as Axis>::Direction::dir_from_index_unchecked(self.index) };
+            self.index += 1;
+            if self.shape.move_coord(self.coord, d.clone()).is_ok() {
+                return Some(d);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(<S::Axis as Axis>::UNDIRECTED_COUNT - self.index))
+    }
+}
+
+impl<'a, S: Shape> core::iter::FusedIterator for ValidDirections<'a, S> {}
+
 /// Direction of axis. It tells which direction is connected to node.
 pub trait AxisDirection: Clone {
     /// Check this match whith [`Axis`]. It will always return true when `Axis` is directed.
@@ -343,13 +473,25 @@ impl Offset {
     pub fn vertical(&self) -> usize {
         self.vertical
     }
+    /// Add `x` to the horizontal. Returns `None` if it overflows.
     #[inline]
-    pub(crate) fn add_x(&self, x: usize) -> Self {
-        Offset::new(self.horizontal + x, self.vertical)
+    pub(crate) fn checked_add_x(&self, x: usize) -> Option<Self> {
+        Some(Offset::new(self.horizontal.checked_add(x)?, self.vertical))
     }
+    /// Add `y` to the vertical. Returns `None` if it overflows.
     #[inline]
-    pub(crate) fn add_y(&self, y: usize) -> Self {
-        Offset::new(self.horizontal, self.vertical + y)
+    pub(crate) fn checked_add_y(&self, y: usize) -> Option<Self> {
+        Some(Offset::new(self.horizontal, self.vertical.checked_add(y)?))
+    }
+    /// Add `x` to the horizontal. Returns `None` if it overflows or is not less than `x_max`.
+    #[inline]
+    pub(crate) fn try_add_x(&self, x: usize, x_max: usize) -> Option<Self> {
+        self.checked_add_x(x)?.check_x(x_max)
+    }
+    /// Add `y` to the vertical. Returns `None` if it overflows or is not less than `y_max`.
+    #[inline]
+    pub(crate) fn try_add_y(&self, y: usize, y_max: usize) -> Option<Self> {
+        self.checked_add_y(y)?.check_y(y_max)
     }
     #[inline]
     pub(crate) fn set_x(&self, x: usize) -> Self {
@@ -404,3 +546,32 @@ impl<T: From<usize>> From<Offset> for (T, T) {
         (x.horizontal.into(), x.vertical.into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_try_add() {
+        let o = Offset::new(3, 4);
+        assert_eq!(o.try_add_x(1, 5), Some(Offset::new(4, 4)));
+        assert_eq!(o.try_add_x(2, 5), None);
+        assert_eq!(o.try_add_y(1, 5), None);
+        assert_eq!(o.try_add_y(1, 6), Some(Offset::new(3, 5)));
+        assert_eq!(
+            o.checked_add_x(usize::MAX - 3),
+            Some(Offset::new(usize::MAX, 4))
+        );
+
+        let o = Offset::new(usize::MAX, usize::MAX - 1);
+        assert_eq!(o.checked_add_x(1), None);
+        assert_eq!(
+            o.checked_add_y(1),
+            Some(Offset::new(usize::MAX, usize::MAX))
+        );
+        assert_eq!(o.checked_add_y(2), None);
+        assert_eq!(o.try_add_x(1, usize::MAX), None);
+        assert_eq!(o.try_add_y(2, usize::MAX), None);
+        assert_eq!(o.try_add_y(1, usize::MAX), None);
+    }
+}
@@ -2,7 +2,10 @@
 //! Use this for directed graph or square with diagonal direction graph.
 
 use super::*;
-use petgraph::{Directed, Undirected};
+use crate::square::NodeIndex;
+#[cfg(feature = "glam")]
+pub mod glam;
+use petgraph::{graph::IndexType, Directed, Undirected};
 
 /// Undirected Square Graph based on [`LatticeGraph`], recommended to use [`SquareGraph`](`crate::SquareGraph`) instead.
 pub type SquareGraphAbstract<N, E> = LatticeGraph<N, E, SquareShape>;
@@ -20,6 +23,10 @@ pub enum SquareAxis {
     Y = 1,
 }
 
+impl AxisNames for SquareAxis {
+    const NAMES: &'static [&'static str] = &["X", "Y"];
+}
+
 impl Axis for SquareAxis {
     const COUNT: usize = 2;
     const DIRECTED: bool = false;
@@ -71,10 +78,103 @@ impl Axis for SquareAxis {
     }
 }
 
+/// Index type of [`SquareOffset`] and [`SquareShape`].
+/// `usize` stores the [`Offset`] as it is, and the smaller types store it in [`NodeIndex`]
+/// to reduce the size of the coordinate.
+pub trait SquareIndex: IndexType {
+    /// Storage of the offset in [`SquareOffset`].
+    type Storage: Copy + core::fmt::Debug + Default + Eq + Ord + core::hash::Hash;
+    /// Convert the storage to [`Offset`].
+    fn storage_to_offset(storage: Self::Storage) -> Offset;
+    /// Convert [`Offset`] to the storage. Returns [`None`] if the offset doesn't fit in `Self`.
+    fn offset_to_storage(offset: Offset) -> Option<Self::Storage>;
+}
+
+impl SquareIndex for usize {
+    type Storage = Offset;
+
+    #[inline]
+    fn storage_to_offset(storage: Offset) -> Offset {
+        storage
+    }
+
+    #[inline]
+    fn offset_to_storage(offset: Offset) -> Option<Offset> {
+        Some(offset)
+    }
+}
+
+macro_rules! impl_square_index {
+    ($($t:ty),*) => {$(
+        impl SquareIndex for $t {
+            type Storage = NodeIndex<$t>;
+
+            #[inline]
+            fn storage_to_offset(storage: NodeIndex<$t>) -> Offset {
+                Offset::new(storage.horizontal.index(), storage.vertical.index())
+            }
+
+            #[inline]
+            fn offset_to_storage(offset: Offset) -> Option<NodeIndex<$t>> {
+                Some(NodeIndex::new(
+                    <$t as core::convert::TryFrom<usize>>::try_from(offset.horizontal).ok()?,
+                    <$t as core::convert::TryFrom<usize>>::try_from(offset.vertical).ok()?,
+                ))
+            }
+        }
+    )*};
+}
+
+impl_square_index!(u8, u16, u32);
+
 /// Offset for square lattice graph.
+/// The index type `Ix` other than `usize` makes it compact. See [`SquareIndex`].
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
-pub struct SquareOffset(pub Offset);
+pub struct SquareOffset<Ix: SquareIndex = usize>(pub Ix::Storage);
+
+impl<Ix: SquareIndex> SquareOffset<Ix> {
+    /// Create a new offset.
+    ///
+    /// # Panics
+    /// Panics if the offset doesn't fit in `Ix`. Use [`try_new`](`Self::try_new`) to check it.
+    pub fn new(horizontal: usize, vertical: usize) -> Self {
+        Self::try_new(horizontal, vertical).unwrap_or_else(|| {
+            panic!(
+                "offset ({}, {}) exceeds the index type",
+                horizontal, vertical
+            )
+        })
+    }
+
+    /// Create a new offset, or returns [`None`] if the offset doesn't fit in `Ix`.
+    pub fn try_new(horizontal: usize, vertical: usize) -> Option<Self> {
+        Ix::offset_to_storage(Offset::new(horizontal, vertical)).map(SquareOffset)
+    }
+
+    /// Get the [`Offset`].
+    pub fn offset(&self) -> Offset {
+        Ix::storage_to_offset(self.0)
+    }
+}
+
+impl SquareOffset {
+    /// Convert to the [`NodeIndex`] of [`SquareGraph`](`crate::SquareGraph`) with the index type `Ix`.
+    /// Returns `None` if the offset doesn't fit in `Ix`.
+    ///
+    /// This is not `TryFrom`, since it would conflict with `From<SquareOffset> for NodeIndex<usize>`.
+    pub fn to_node_index<Ix: IndexType>(&self) -> Option<NodeIndex<Ix>> {
+        let max = <Ix as IndexType>::max().index();
+        if self.0.horizontal <= max && self.0.vertical <= max {
+            Some(NodeIndex::new(
+                Ix::new(self.0.horizontal),
+                Ix::new(self.0.vertical),
+            ))
+        } else {
+            None
+        }
+    }
+}
 
 impl PartialEq<(usize, usize)> for SquareOffset {
     fn eq(&self, other: &(usize, usize)) -> bool {
@@ -91,52 +191,142 @@ impl From<(usize, usize)> for SquareOffset {
     }
 }
 
-impl Coordinate for SquareOffset {}
+impl<Ix: IndexType> From<NodeIndex<Ix>> for SquareOffset {
+    fn from(x: NodeIndex<Ix>) -> Self {
+        SquareOffset(Offset {
+            horizontal: x.horizontal.index(),
+            vertical: x.vertical.index(),
+        })
+    }
+}
+
+impl From<SquareOffset> for NodeIndex<usize> {
+    fn from(x: SquareOffset) -> Self {
+        NodeIndex::new(x.0.horizontal, x.0.vertical)
+    }
+}
+
+impl<Ix: IndexType> PartialEq<NodeIndex<Ix>> for SquareOffset {
+    fn eq(&self, other: &NodeIndex<Ix>) -> bool {
+        self.0.horizontal == other.horizontal.index() && self.0.vertical == other.vertical.index()
+    }
+}
+
+impl<Ix: IndexType> PartialEq<SquareOffset> for NodeIndex<Ix> {
+    fn eq(&self, other: &SquareOffset) -> bool {
+        other == self
+    }
+}
+
+impl<Ix: SquareIndex> Coordinate for SquareOffset<Ix> {}
+
+/// [`Metric`] of the sum of the horizontal and vertical distance, the steps in [`SquareShape`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Manhattan;
+
+impl Metric<SquareOffset> for Manhattan {
+    type Distance = usize;
+    fn distance(&self, a: SquareOffset, b: SquareOffset) -> usize {
+        let (a, b) = (a.0, b.0);
+        a.horizontal.abs_diff(b.horizontal) + a.vertical.abs_diff(b.vertical)
+    }
+}
+
+/// [`Metric`] of the max of the horizontal and vertical distance, the steps in [`SquareDiagonalShape`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chebyshev;
+
+impl Metric<SquareOffset> for Chebyshev {
+    type Distance = usize;
+    fn distance(&self, a: SquareOffset, b: SquareOffset) -> usize {
+        let (a, b) = (a.0, b.0);
+        a.horizontal
+            .abs_diff(b.horizontal)
+            .max(a.vertical.abs_diff(b.vertical))
+    }
+}
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 /// Shape for Square Graph.
-pub struct SquareShape<E = Undirected> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(bound = ""))]
+pub struct SquareShape<E = Undirected, Ix = usize> {
     h: usize,
     v: usize,
     e: PhantomData<E>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    ix: PhantomData<Ix>,
 }
 
-impl<E> SquareShape<E> {
+impl<E, Ix: SquareIndex> SquareShape<E, Ix> {
     /// Create a new graph.
+    ///
+    /// # Panics
+    /// Panics if the offset of the node doesn't fit in `Ix`.
     pub fn new(h: usize, v: usize) -> Self {
+        assert!(Self::fits(h, v), "shape {}x{} exceeds the index type", h, v);
         Self {
             h,
             v,
             e: PhantomData,
+            ix: PhantomData,
+        }
+    }
+
+    fn fits(h: usize, v: usize) -> bool {
+        let max = <Ix as IndexType>::max().index();
+        h.saturating_sub(1) <= max && v.saturating_sub(1) <= max
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, E, Ix: SquareIndex> serde::Deserialize<'de> for SquareShape<E, Ix> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "SquareShape")]
+        struct Size<E> {
+            h: usize,
+            v: usize,
+            #[allow(dead_code)]
+            e: PhantomData<E>,
         }
+        let Size { h, v, .. } = Size::<E>::deserialize(deserializer)?;
+        if !Self::fits(h, v) {
+            return Err(D::Error::custom("shape exceeds the index type"));
+        }
+        Ok(Self::new(h, v))
     }
 }
 
-fn range_check<S: Shape>(s: S, coord: SquareOffset) -> Result<Offset, ()> {
-    if coord.0.horizontal < s.horizontal() && coord.0.vertical < s.vertical() {
-        Ok(coord.0)
+fn range_check<S: Shape, Ix: SquareIndex>(s: S, coord: SquareOffset<Ix>) -> Result<Offset, ()> {
+    let o = coord.offset();
+    if o.horizontal < s.horizontal() && o.vertical < s.vertical() {
+        Ok(o)
     } else {
         Err(())
     }
 }
 
-fn move_coord<S: Shape>(
+fn move_coord<S: Shape, Ix: SquareIndex>(
     s: S,
-    coord: SquareOffset,
+    coord: SquareOffset<Ix>,
     dir: DirectedSquareAxis,
-) -> Result<SquareOffset, ()> {
+) -> Result<SquareOffset<Ix>, ()> {
+    let o = coord.offset();
     let o = match dir {
-        DirectedSquareAxis::X => coord.0.add_x(1).check_x(s.horizontal()),
-        DirectedSquareAxis::Y => coord.0.add_y(1).check_y(s.vertical()),
-        DirectedSquareAxis::RX => coord.0.sub_x(1),
-        DirectedSquareAxis::RY => coord.0.sub_y(1),
+        DirectedSquareAxis::X => o.try_add_x(1, s.horizontal()),
+        DirectedSquareAxis::Y => o.try_add_y(1, s.vertical()),
+        DirectedSquareAxis::RX => o.sub_x(1),
+        DirectedSquareAxis::RY => o.sub_y(1),
     };
-    o.map(|s| SquareOffset(s)).ok_or_else(|| ())
+    o.and_then(Ix::offset_to_storage)
+        .map(SquareOffset)
+        .ok_or(())
 }
 
-impl Shape for SquareShape {
+impl<Ix: SquareIndex> Shape for SquareShape<Undirected, Ix> {
     type Axis = SquareAxis;
-    type Coordinate = SquareOffset;
+    type Coordinate = SquareOffset<Ix>;
     type OffsetConvertError = ();
     type CoordinateMoveError = ();
 
@@ -147,12 +337,12 @@ impl Shape for SquareShape {
 
     #[inline]
     unsafe fn to_offset_unchecked(&self, coord: Self::Coordinate) -> Offset {
-        coord.0
+        coord.offset()
     }
 
     #[inline]
     fn from_offset(&self, offset: Offset) -> Self::Coordinate {
-        SquareOffset(offset)
+        SquareOffset::new(offset.horizontal, offset.vertical)
     }
 
     #[inline]
@@ -181,7 +371,11 @@ impl Shape for SquareShape {
         }
     }
 
-    fn move_coord(&self, coord: SquareOffset, dir: DirectedSquareAxis) -> Result<SquareOffset, ()> {
+    fn move_coord(
+        &self,
+        coord: SquareOffset<Ix>,
+        dir: DirectedSquareAxis,
+    ) -> Result<SquareOffset<Ix>, ()> {
         move_coord(self, coord, dir)
     }
 }
@@ -195,6 +389,10 @@ pub enum DirectedSquareAxis {
     RY = 3,
 }
 
+impl AxisNames for DirectedSquareAxis {
+    const NAMES: &'static [&'static str] = &["X", "Y", "RX", "RY"];
+}
+
 impl Axis for DirectedSquareAxis {
     const COUNT: usize = 4;
     const DIRECTED: bool = true;
@@ -225,7 +423,7 @@ impl Axis for DirectedSquareAxis {
 
     fn backward(self) -> Self::Direction {
         let x = self.to_index();
-        let x2 = if x > 2 { x - 2 } else { x + 2 };
+        let x2 = if x >= 2 { x - 2 } else { x + 2 };
         unsafe { Self::from_index_unchecked(x2) }
     }
 
@@ -234,9 +432,9 @@ impl Axis for DirectedSquareAxis {
     }
 }
 
-impl Shape for SquareShape<petgraph::Directed> {
+impl<Ix: SquareIndex> Shape for SquareShape<petgraph::Directed, Ix> {
     type Axis = DirectedSquareAxis;
-    type Coordinate = SquareOffset;
+    type Coordinate = SquareOffset<Ix>;
     type OffsetConvertError = ();
     type CoordinateMoveError = ();
 
@@ -253,7 +451,7 @@ impl Shape for SquareShape<petgraph::Directed> {
     }
 
     fn from_offset(&self, offset: Offset) -> Self::Coordinate {
-        SquareOffset(offset)
+        SquareOffset::new(offset.horizontal, offset.vertical)
     }
 
     fn move_coord(
@@ -274,6 +472,10 @@ pub enum SquareDiagonalAxis {
     SE,
 }
 
+impl AxisNames for SquareDiagonalAxis {
+    const NAMES: &'static [&'static str] = &["N", "NE", "E", "SE"];
+}
+
 impl Axis for SquareDiagonalAxis {
     const COUNT: usize = 4;
     const DIRECTED: bool = false;
@@ -335,6 +537,10 @@ pub enum DirectedSquareDiagonalAxis {
     NW,
 }
 
+impl AxisNames for DirectedSquareDiagonalAxis {
+    const NAMES: &'static [&'static str] = &["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+}
+
 impl Axis for DirectedSquareDiagonalAxis {
     const COUNT: usize = 8;
     const DIRECTED: bool = true;
@@ -402,6 +608,11 @@ impl Axis for DirectedSquareDiagonalAxis {
 
 /// Shape for lattice graph with Square and Diagonal Edge.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct SquareDiagonalShape<E = Undirected> {
     h: usize,
     v: usize,
@@ -456,32 +667,53 @@ impl Shape for SquareDiagonalShape {
     ) -> Result<Self::Coordinate, Self::CoordinateMoveError> {
         let offset = coord.0;
         match dir {
-            DirectedSquareDiagonalAxis::N => offset.add_y(1).check_y(self.vertical()),
+            DirectedSquareDiagonalAxis::N => offset.try_add_y(1, self.vertical()),
             DirectedSquareDiagonalAxis::NE => offset
-                .add_x(1)
-                .check_x(self.horizontal())
-                .map(|o| o.add_y(1).check_y(self.vertical()))
-                .flatten(),
-            DirectedSquareDiagonalAxis::E => offset.add_x(1).check_x(self.horizontal()),
+                .try_add_x(1, self.horizontal())
+                .and_then(|o| o.try_add_y(1, self.vertical())),
+            DirectedSquareDiagonalAxis::E => offset.try_add_x(1, self.horizontal()),
             DirectedSquareDiagonalAxis::SE => offset
-                .add_x(1)
-                .check_x(self.horizontal())
-                .map(|o| o.sub_y(1))
-                .flatten(),
+                .try_add_x(1, self.horizontal())
+                .and_then(|o| o.sub_y(1)),
 
             DirectedSquareDiagonalAxis::S => offset.sub_y(1),
-            DirectedSquareDiagonalAxis::SW => offset.sub_x(1).map(|o| o.sub_y(1)).flatten(),
+            DirectedSquareDiagonalAxis::SW => offset.sub_x(1).and_then(|o| o.sub_y(1)),
             DirectedSquareDiagonalAxis::W => offset.sub_x(1),
             DirectedSquareDiagonalAxis::NW => offset
                 .sub_x(1)
-                .map(|o| o.add_y(1).check_y(self.vertical()))
-                .flatten(),
+                .and_then(|o| o.try_add_y(1, self.vertical())),
         }
         .map(|x| SquareOffset(x))
         .ok_or(())
     }
 }
 
+impl<N, E> DiagonalSquareGraph<N, E> {
+    /// Iterate the neighbors to the diagonal directions without cutting the corner,
+    /// that is, both of the nodes adjacent to the node and the neighbor orthogonally are not blocked.
+    /// Whether the neighbor itself is blocked is not checked.
+    pub fn diagonal_neighbors_no_corner_cut<'a>(
+        &'a self,
+        c: SquareOffset,
+        is_blocked: impl Fn(SquareOffset) -> bool + 'a,
+    ) -> impl Iterator<Item = SquareOffset> + 'a {
+        use DirectedSquareDiagonalAxis::*;
+        let inside = self.s.to_offset(c).is_ok();
+        IntoIterator::into_iter([(NE, N, E), (SE, S, E), (SW, S, W), (NW, N, W)])
+            .filter(move |_| inside)
+            .filter_map(move |(d, a, b)| {
+                let t = self.s.move_coord(c, d).ok()?;
+                let a = self.s.move_coord(c, a).ok()?;
+                let b = self.s.move_coord(c, b).ok()?;
+                if is_blocked(a) || is_blocked(b) {
+                    None
+                } else {
+                    Some(t)
+                }
+            })
+    }
+}
+
 impl Shape for SquareDiagonalShape<Directed> {
     type Axis = DirectedSquareDiagonalAxis;
     type Coordinate = SquareOffset;
@@ -519,26 +751,21 @@ impl Shape for SquareDiagonalShape<Directed> {
     ) -> Result<Self::Coordinate, Self::CoordinateMoveError> {
         let offset = coord.0;
         match dir {
-            DirectedSquareDiagonalAxis::N => offset.add_y(1).check_y(self.vertical()),
+            DirectedSquareDiagonalAxis::N => offset.try_add_y(1, self.vertical()),
             DirectedSquareDiagonalAxis::NE => offset
-                .add_x(1)
-                .check_x(self.horizontal())
-                .map(|o| o.add_y(1).check_y(self.vertical()))
-                .flatten(),
-            DirectedSquareDiagonalAxis::E => offset.add_x(1).check_x(self.horizontal()),
+                .try_add_x(1, self.horizontal())
+                .and_then(|o| o.try_add_y(1, self.vertical())),
+            DirectedSquareDiagonalAxis::E => offset.try_add_x(1, self.horizontal()),
             DirectedSquareDiagonalAxis::SE => offset
-                .add_x(1)
-                .check_x(self.horizontal())
-                .map(|o| o.sub_y(1))
-                .flatten(),
+                .try_add_x(1, self.horizontal())
+                .and_then(|o| o.sub_y(1)),
 
             DirectedSquareDiagonalAxis::S => offset.sub_y(1),
-            DirectedSquareDiagonalAxis::SW => offset.sub_x(1).map(|o| o.sub_y(1)).flatten(),
+            DirectedSquareDiagonalAxis::SW => offset.sub_x(1).and_then(|o| o.sub_y(1)),
             DirectedSquareDiagonalAxis::W => offset.sub_x(1),
             DirectedSquareDiagonalAxis::NW => offset
                 .sub_x(1)
-                .map(|o| o.add_y(1).check_y(self.vertical()))
-                .flatten(),
+                .and_then(|o| o.try_add_y(1, self.vertical())),
         }
         .map(|x| SquareOffset(x))
         .ok_or(())
@@ -621,6 +848,27 @@ mod tests {
         assert_eq!(count, 12);
     }
 
+    #[test]
+    fn node_references() {
+        let sq = SquareGraph::new_with(
+            SquareShape::new(4, 3),
+            |SquareOffset(Offset {
+                 horizontal: x,
+                 vertical: y,
+             })| x + 2 * y,
+            |_, _| (),
+        );
+        let refs = sq.node_references();
+        assert_eq!(refs.len(), 12);
+        assert!(refs.clone().map(|(c, _)| c).eq(sq.node_identifiers()));
+        for (c, w) in refs {
+            assert!(std::ptr::eq(w, sq.node_weight(c).unwrap()));
+        }
+        let mut refs = sq.node_references();
+        refs.next();
+        assert_eq!(refs.len(), 11);
+    }
+
     #[test]
     fn neighbors() {
         let sq = SquareGraph::new_with(
@@ -650,6 +898,14 @@ mod tests {
 
         let v12 = sq.neighbors((1, 2).into());
         debug_assert!(v12.eq(IntoIter::new([(2, 2), (1, 3), (0, 2), (1, 1)])));
+
+        assert_eq!(sq.degree((0, 0).into()), 2);
+        assert_eq!(sq.degree((0, 2).into()), 3);
+        assert_eq!(sq.degree((1, 2).into()), 4);
+        assert_eq!(sq.degree((3, 0).into()), 0);
+        for c in sq.node_identifiers() {
+            assert_eq!(sq.degree(c), sq.neighbors(c).count());
+        }
     }
 
     #[test]
@@ -684,6 +940,29 @@ mod tests {
             .eq(IntoIter::new([(2, 2), (1, 3), (0, 2), (1, 1)])));
     }
 
+    #[test]
+    fn weight_bounds() {
+        let sq = SquareGraph::new_with(
+            SquareShape::new(4, 3),
+            |SquareOffset(Offset {
+                 horizontal: x,
+                 vertical: y,
+             })| x + 2 * y,
+            |SquareOffset(Offset {
+                 horizontal: x,
+                 vertical: y,
+             }),
+             d| (x + 2 * y) as i32 * if d == SquareAxis::X { 1 } else { -1 },
+        );
+        assert_eq!(sq.node_weight_bounds(), Some((&0, &7)));
+        // max: (2, 2) X, min: (3, 1) Y
+        assert_eq!(sq.edge_weight_bounds(), Some((&-5, &6)));
+
+        let line = SquareGraph::<i32, i32>::new_with(SquareShape::new(1, 1), |_| 1, |_, _| 1);
+        assert_eq!(line.node_weight_bounds(), Some((&1, &1)));
+        assert_eq!(line.edge_weight_bounds(), None);
+    }
+
     #[test]
     fn astar() {
         let sq = SquareGraph::new_with(
@@ -723,4 +1002,669 @@ mod tests {
         assert_eq!(d, 5);
         assert_eq!(p, [(2, 1), (1, 1), (0, 1), (0, 0)])
     }
+
+    #[test]
+    fn node_index_conversion() {
+        for &(x, y) in &[(0, 0), (3, 1), (7, 12)] {
+            let o = SquareOffset::from((x, y));
+            let n = NodeIndex::<usize>::from(o);
+            assert_eq!(n, NodeIndex::new(x, y));
+            assert_eq!(SquareOffset::from(n), o);
+            let n32 = NodeIndex::<u32>::new(x as u32, y as u32);
+            assert_eq!(SquareOffset::from(n32), o);
+        }
+    }
+
+    #[test]
+    fn same_shape_as() {
+        let a = SquareGraph::<(), ()>::new(SquareShape::new(4, 3));
+        let b = SquareGraph::<i32, u8>::new(SquareShape::new(4, 3));
+        let c = SquareGraph::<(), ()>::new(SquareShape::new(3, 4));
+        let d = SquareGraph::<(), ()>::new(SquareShape::new(4, 2));
+        assert!(a.same_shape_as(&a));
+        assert!(a.same_shape_as(&b));
+        assert!(b.same_shape_as(&a));
+        assert!(!a.same_shape_as(&c));
+        assert!(!a.same_shape_as(&d));
+    }
+
+    #[test]
+    fn diameter() {
+        let line = SquareGraph::<(), u32>::new_with(SquareShape::new(6, 1), |_| (), |_, _| 1);
+        assert_eq!(line.diameter(|e| *e.weight()), line.node_count() as u32 - 1);
+        let sq = SquareGraph::<(), u32>::new_with(SquareShape::new(3, 4), |_| (), |_, _| 2);
+        assert_eq!(sq.diameter(|e| *e.weight()), 10);
+        let single = SquareGraph::<(), u32>::new_with(SquareShape::new(1, 1), |_| (), |_, _| 1);
+        assert_eq!(single.diameter(|e| *e.weight()), 0);
+    }
+
+    #[test]
+    fn edge_arrays() {
+        let mut sq = SquareGraph::<(), usize>::new_with(
+            SquareShape::new(4, 3),
+            |_| (),
+            |SquareOffset(o), a| o.horizontal * 10 + o.vertical + a as usize * 100,
+        );
+        let x = &sq.edge_arrays()[SquareAxis::X.to_index()];
+        for h in 0..3 {
+            for v in 0..3 {
+                let c = SquareOffset::from((h, v));
+                assert_eq!(Some(&x.ref_2d()[h][v]), sq.edge_weight((c, SquareAxis::X)));
+            }
+        }
+        unsafe {
+            let y = &mut sq.edge_arrays_mut()[SquareAxis::Y.to_index()];
+            y.mut_2d()[1][1] += 1000;
+        }
+        assert_eq!(sq.edge_weight(((1, 1).into(), SquareAxis::Y)), Some(&1111));
+    }
+
+    #[test]
+    fn incident_edge_ids() {
+        let sq = SquareGraph::<(), (SquareOffset, SquareAxis)>::new_with(
+            SquareShape::new(4, 3),
+            |_| (),
+            |c, a| (c, a),
+        );
+        for c in sq.node_identifiers() {
+            let ids: Vec<_> = sq.incident_edge_ids(c).collect();
+            assert_eq!(ids.len(), sq.neighbors(c).count());
+            for (id, e) in ids.iter().zip(sq.edges(c)) {
+                assert_eq!(sq.edge_weight(*id), Some(id));
+                assert_eq!(e.weight(), id);
+            }
+        }
+        assert!(sq
+            .incident_edge_ids((1, 1).into())
+            .eq(IntoIterator::into_iter([
+                ((1, 1).into(), SquareAxis::X),
+                ((1, 1).into(), SquareAxis::Y),
+                ((0, 1).into(), SquareAxis::X),
+                ((1, 0).into(), SquareAxis::Y),
+            ])));
+    }
+
+    #[test]
+    fn write_nodes() {
+        let mut sq = SquareGraph::<usize, ()>::new(SquareShape::new(3, 2));
+        sq.write_nodes(vec![1, 2, 3, 4]);
+        for i in 0..sq.node_count() {
+            let expected = if i < 4 { i + 1 } else { 0 };
+            assert_eq!(sq.node_weight(sq.from_index(i)), Some(&expected));
+        }
+        assert_eq!(sq.node_weight((0, 1).into()), Some(&2));
+        assert_eq!(sq.node_weight((1, 0).into()), Some(&3));
+        sq.write_nodes(10..);
+        assert_eq!(sq.node_weight((2, 1).into()), Some(&15));
+    }
+
+    #[test]
+    #[cfg(feature = "stable_graph")]
+    fn to_stable_graph() {
+        let sq = SquareGraph::<SquareOffset, i32>::new_with(
+            SquareShape::new(4, 3),
+            |c| c,
+            |c, a| {
+                (c.0.horizontal * 10 + c.0.vertical) as i32
+                    * if a == SquareAxis::X { 1 } else { -1 }
+            },
+        );
+        let mut g = sq.to_stable_graph();
+        assert!(!g.is_directed());
+        assert_eq!(g.node_count(), sq.node_count());
+        assert_eq!(g.edge_count(), sq.edge_references().count());
+        for i in 0..sq.node_count() {
+            let c = sq.from_index(i);
+            let n = petgraph::graph::node_index(i);
+            assert_eq!(g[n], c);
+            assert_eq!(g.neighbors(n).count(), sq.neighbors(c).count());
+        }
+        let e = g
+            .find_edge(
+                petgraph::graph::node_index(4),
+                petgraph::graph::node_index(3),
+            )
+            .unwrap();
+        assert_eq!(g[e], -10);
+        g.remove_node(petgraph::graph::node_index(0));
+        assert_eq!(g.neighbors(petgraph::graph::node_index(1)).count(), 2);
+
+        let directed = LatticeGraph::<(), (), SquareShape<Directed>>::new(SquareShape::new(4, 3));
+        let g = directed.to_stable_graph();
+        assert!(g.is_directed());
+        assert_eq!(g.edge_count(), directed.edge_references().count());
+    }
+
+    #[test]
+    fn passable_neighbors() {
+        let sq = SquareGraph::<(), u32>::new_with(
+            SquareShape::new(3, 3),
+            |_| (),
+            |c, a| {
+                if c == (1, 1) && a == SquareAxis::X {
+                    10
+                } else {
+                    1
+                }
+            },
+        );
+        let c = SquareOffset::from((1, 1));
+        assert!(sq
+            .passable_neighbors(c, 1)
+            .eq(IntoIterator::into_iter([(1, 2), (0, 1), (1, 0)])));
+        assert!(sq.passable_neighbors(c, 10).eq(sq.neighbors(c)));
+        assert_eq!(sq.passable_neighbors((2, 1).into(), 5).count(), 2);
+        assert_eq!(sq.passable_neighbors(c, 0).count(), 0);
+    }
+
+    #[test]
+    fn cheapest_neighbor() {
+        let sq = SquareGraph::<(), usize>::new_with(
+            SquareShape::new(3, 3),
+            |_| (),
+            |c, a| c.0.horizontal * 4 + c.0.vertical * 2 + if a == SquareAxis::X { 0 } else { 1 },
+        );
+        assert_eq!(
+            sq.cheapest_neighbor((1, 1).into()),
+            Some(((0, 1).into(), &2))
+        );
+        assert_eq!(
+            sq.cheapest_neighbor((2, 2).into()),
+            Some(((1, 2).into(), &8))
+        );
+        assert_eq!(sq.cheapest_neighbor((3, 0).into()), None);
+    }
+
+    #[test]
+    fn coords_by_distance() {
+        let graph = SquareGraph::<(), ()>::new(SquareShape::new(4, 3));
+        let center = SquareOffset::from((1, 1));
+        let coords = graph.coords_by_distance(center, Manhattan);
+        assert_eq!(coords.len(), 12);
+        assert_eq!(coords[0], center);
+        assert!(coords
+            .windows(2)
+            .all(|w| Manhattan.distance(center, w[0]) <= Manhattan.distance(center, w[1])));
+        assert_eq!(coords[11], (3, 2));
+        let coords = graph.coords_by_distance(center, Chebyshev);
+        assert_eq!(coords[0], center);
+        assert_eq!(coords[9..], [(3, 0), (3, 1), (3, 2)]);
+        let coords = graph.coords_by_distance(center, |a: SquareOffset, b: SquareOffset| {
+            a.0.vertical.abs_diff(b.0.vertical)
+        });
+        assert_eq!(coords[..4], [(0, 1), (1, 1), (2, 1), (3, 1)]);
+    }
+
+    #[test]
+    fn adjacency_matrix() {
+        let graph = DirectedSquareGraph::<(), ()>::new(SquareShape::new(3, 2));
+        let check = |graph: &DirectedSquareGraph<(), ()>| {
+            let m = graph.adjacency_matrix();
+            for a in (0..4).flat_map(|h| (0..3).map(move |v| SquareOffset::from((h, v)))) {
+                for b in (0..4).flat_map(|h| (0..3).map(move |v| SquareOffset::from((h, v)))) {
+                    assert_eq!(
+                        graph.is_adjacent(&m, a, b),
+                        graph.edges(a).any(|e| e.target() == b),
+                        "{:?} {:?}",
+                        a,
+                        b
+                    );
+                }
+            }
+            assert!(graph.is_adjacent(&m, (0, 0).into(), (1, 0).into()));
+            assert!(!graph.is_adjacent(&m, (3, 0).into(), (2, 0).into()));
+        };
+        check(&graph);
+        check(&graph.with_connectivity_cache());
+    }
+
+    #[test]
+    fn by_weight() {
+        use std::{cmp::Reverse, collections::BinaryHeap};
+        let sq = SquareGraph::<(), usize>::new_with(
+            SquareShape::new(3, 3),
+            |_| (),
+            |c, a| c.0.horizontal * 4 + c.0.vertical * 2 + if a == SquareAxis::X { 0 } else { 1 },
+        );
+        let mut heap = BinaryHeap::new();
+        for c in [(1, 1), (2, 2)] {
+            for e in sq.edges(c.into()) {
+                heap.push(Reverse(ByWeight(e)));
+            }
+        }
+        let weights = std::iter::from_fn(|| heap.pop())
+            .map(|Reverse(ByWeight(e))| *e.weight())
+            .collect::<Vec<_>>();
+        assert_eq!(weights, vec![2, 5, 6, 7, 8, 11]);
+    }
+
+    #[test]
+    fn edges_grouped_by_source() {
+        fn check<S>(graph: &LatticeGraph<(), (), S>)
+        where
+            S: Shape<Coordinate = SquareOffset>,
+            <S::Axis as Axis>::Direction: Copy + PartialEq + std::fmt::Debug,
+            S::Axis: std::fmt::Debug,
+        {
+            let groups = graph.edges_grouped_by_source().collect::<Vec<_>>();
+            let sources = groups
+                .iter()
+                .map(|g| g.0)
+                .collect::<std::collections::HashSet<_>>();
+            assert_eq!(sources.len(), groups.len());
+            assert!(groups
+                .iter()
+                .all(|(c, es)| es.iter().all(|e| e.source() == *c)));
+            let edges = groups.into_iter().flat_map(|g| g.1).collect::<Vec<_>>();
+            assert_eq!(edges, graph.edge_references().collect::<Vec<_>>());
+        }
+        let graph = DirectedSquareGraph::<(), ()>::new(SquareShape::new(3, 2));
+        check(&graph);
+        assert_eq!(graph.edges_grouped_by_source().count(), 6);
+        let graph = SquareGraph::<(), ()>::new(SquareShape::new(3, 2));
+        check(&graph);
+        // (2, 1) has no forward edges
+        assert_eq!(graph.edges_grouped_by_source().count(), 5);
+    }
+
+    #[test]
+    fn edges_by_offset() {
+        let sq = SquareGraph::<(), (SquareOffset, SquareAxis)>::new_with(
+            SquareShape::new(3, 2),
+            |_| (),
+            |c, a| (c, a),
+        );
+        let edges = sq.edges_by_offset().collect::<Vec<_>>();
+        assert_eq!(edges.len(), 2 * 2 + 3);
+        for (o, a, w) in edges {
+            let c = sq.shape().from_offset(o);
+            let a = SquareAxis::from_index(a).unwrap();
+            assert_eq!(sq.edge_weight((c, a)), Some(w));
+            assert_eq!(w, &(c, a));
+        }
+    }
+
+    #[test]
+    fn drop_edges() {
+        let sq = SquareGraph::<String, String>::new_with(
+            SquareShape::new(3, 2),
+            |c| format!("{:?}", c),
+            |c, a| format!("{:?}{:?}", c, a),
+        );
+        assert_eq!(
+            sq.edge_weight(((0, 0).into(), SquareAxis::X)),
+            Some(&"SquareOffset(Offset { horizontal: 0, vertical: 0 })X".to_string())
+        );
+        drop(sq);
+    }
+
+    #[test]
+    fn map() {
+        let sq = SquareGraph::<f32, f32>::new_with(
+            SquareShape::new(3, 2),
+            |c| (c.0.horizontal * 10 + c.0.vertical) as f32 / 2.,
+            |c, a| c.0.horizontal as f32 + if a == SquareAxis::X { 0.5 } else { 0.25 },
+        );
+        let mapped = sq.map(
+            |c, n| format!("{}:{}", c.0.horizontal, n),
+            |_, a, e| format!("{:?}{}", a, e),
+        );
+        assert!(mapped.same_shape_as(&sq));
+        assert_eq!(
+            mapped.node_weight((2, 1).into()),
+            Some(&"2:10.5".to_string())
+        );
+        assert_eq!(
+            mapped.edge_weight(((1, 0).into(), SquareAxis::X)),
+            Some(&"X1.5".to_string())
+        );
+        assert_eq!(
+            mapped.edge_weight(((2, 0).into(), SquareAxis::Y)),
+            Some(&"Y2.25".to_string())
+        );
+        assert_eq!(mapped.edge_weight(((2, 0).into(), SquareAxis::X)), None);
+        assert_eq!(
+            mapped.edge_references().count(),
+            sq.edge_references().count()
+        );
+    }
+
+    #[test]
+    fn neighbors_directed() {
+        use petgraph::Direction::{Incoming, Outgoing};
+        use std::collections::HashSet;
+        let graph = DirectedSquareGraph::<(), ()>::new(SquareShape::new(3, 3));
+        let center = SquareOffset::from((1, 1));
+        let expected = [(0, 1), (2, 1), (1, 0), (1, 2)]
+            .iter()
+            .map(|&c| SquareOffset::from(c))
+            .collect::<HashSet<_>>();
+        let outgoing = graph
+            .neighbors_directed(center, Outgoing)
+            .collect::<HashSet<_>>();
+        let incoming = graph
+            .neighbors_directed(center, Incoming)
+            .collect::<HashSet<_>>();
+        assert_eq!(outgoing, expected);
+        assert_eq!(incoming, expected);
+        assert!(graph
+            .edges_directed(center, Outgoing)
+            .all(|e| e.source() == center && outgoing.contains(&e.target())));
+        assert!(graph
+            .edges_directed(center, Incoming)
+            .all(|e| e.target() == center && incoming.contains(&e.source())));
+        let corner = graph
+            .neighbors_directed((0, 0).into(), Incoming)
+            .collect::<Vec<_>>();
+        assert_eq!(corner, [(1, 0), (0, 1)]);
+        for c in graph.node_identifiers() {
+            assert_eq!(graph.degree(c), graph.edges_directed(c, Outgoing).count());
+        }
+
+        let graph = SquareGraph::<(), ()>::new(SquareShape::new(3, 3));
+        for d in [Outgoing, Incoming] {
+            assert_eq!(
+                graph.neighbors_directed(center, d).collect::<Vec<_>>(),
+                graph.neighbors(center).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn laplacian() {
+        let graph = SquareGraph::<(), u32>::new_with(
+            SquareShape::new(3, 2),
+            |_| (),
+            |c, _| c.0.horizontal as u32 + 1,
+        );
+        let n = graph.node_count();
+        let dense = |triplets: Vec<(usize, usize, f64)>| {
+            let mut m = vec![vec![0.0; n]; n];
+            for (i, j, x) in triplets {
+                m[i][j] += x;
+            }
+            m
+        };
+        let m = dense(graph.laplacian());
+        for (i, row) in m.iter().enumerate() {
+            assert_eq!(row.iter().sum::<f64>(), 0.0);
+            let c = graph.shape().from_index(i);
+            assert_eq!(row[i], graph.neighbors(c).count() as f64);
+            for (j, x) in row.iter().enumerate() {
+                assert_eq!(*x, m[j][i]);
+            }
+        }
+        // (0, 0) - (1, 0) with weight 1
+        let (a, b) = (
+            graph.shape().to_index((0, 0).into()).unwrap(),
+            graph.shape().to_index((1, 0).into()).unwrap(),
+        );
+        assert_eq!(m[a][b], -1.0);
+
+        let m = dense(graph.laplacian_with(|w| *w as f64));
+        assert!(m.iter().all(|row| row.iter().sum::<f64>() == 0.0));
+        assert_eq!(m[a][b], -1.0);
+        // (0, 0) has the edges with weight 1 to (1, 0) and (0, 1).
+        assert_eq!(m[a][a], 2.0);
+        // (1, 0) has the edges with weight 1 to (0, 0), 2 to (2, 0) and (1, 1).
+        assert_eq!(m[b][b], 5.0);
+    }
+
+    #[test]
+    fn diagonal_neighbors_no_corner_cut() {
+        let graph = DiagonalSquareGraph::<(), ()>::new(SquareDiagonalShape::new(3, 3));
+        let center = SquareOffset::from((1, 1));
+        let neighbors = |blocked: &[(usize, usize)]| {
+            graph
+                .diagonal_neighbors_no_corner_cut(center, |c| blocked.iter().any(|&b| c == b))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(neighbors(&[]), [(2, 2), (2, 0), (0, 0), (0, 2)]);
+        // (1, 2) is shared by NE and NW.
+        assert_eq!(neighbors(&[(1, 2)]), [(2, 0), (0, 0)]);
+        // blocking both (2, 1) and (1, 0) removes all but NW.
+        assert_eq!(neighbors(&[(2, 1), (1, 0)]), [(0, 2)]);
+        // the diagonal neighbor itself is not checked.
+        assert_eq!(neighbors(&[(2, 2)]), [(2, 2), (2, 0), (0, 0), (0, 2)]);
+
+        let corner = graph
+            .diagonal_neighbors_no_corner_cut((0, 0).into(), |c| c == (0, 1))
+            .collect::<Vec<_>>();
+        assert!(corner.is_empty());
+        assert_eq!(
+            graph
+                .diagonal_neighbors_no_corner_cut((3, 0).into(), |_| false)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn nearest_matching() {
+        let graph = SquareGraph::<u32, ()>::new_with(
+            SquareShape::new(5, 5),
+            |c| match (c.0.horizontal, c.0.vertical) {
+                (4, 0) | (2, 1) | (4, 4) => 1,
+                _ => 0,
+            },
+            |_, _| (),
+        );
+        let nearest = graph.nearest_matching((0, 0).into(), |_, n| *n == 1);
+        assert_eq!(nearest, Some((2, 1).into()));
+        let nearest = graph.nearest_matching((4, 3).into(), |_, n| *n == 1);
+        assert_eq!(nearest, Some((4, 4).into()));
+        // start itself is matched.
+        let nearest = graph.nearest_matching((4, 0).into(), |_, n| *n == 1);
+        assert_eq!(nearest, Some((4, 0).into()));
+        let nearest = graph.nearest_matching((0, 0).into(), |c, _| c.0.vertical == 3);
+        assert_eq!(nearest, Some((0, 3).into()));
+        assert_eq!(graph.nearest_matching((0, 0).into(), |_, n| *n == 2), None);
+        assert_eq!(graph.nearest_matching((5, 0).into(), |_, _| true), None);
+
+        // (2, 2) is nearer than (3, 0) from (0, 0) with the diagonal edges.
+        let weight = |c: SquareOffset| match (c.0.horizontal, c.0.vertical) {
+            (3, 0) | (2, 2) => 1,
+            _ => 0,
+        };
+        let graph = SquareGraph::<u32, ()>::new_with(SquareShape::new(5, 5), weight, |_, _| ());
+        let nearest = graph.nearest_matching((0, 0).into(), |_, n| *n == 1);
+        assert_eq!(nearest, Some((3, 0).into()));
+        let graph = DiagonalSquareGraph::<u32, ()>::new_with(
+            SquareDiagonalShape::new(5, 5),
+            weight,
+            |_, _| (),
+        );
+        let nearest = graph.nearest_matching((0, 0).into(), |_, n| *n == 1);
+        assert_eq!(nearest, Some((2, 2).into()));
+    }
+    #[test]
+    fn move_coord_boundary() {
+        let s: SquareShape = SquareShape::new(3, 2);
+        assert_eq!(
+            s.move_coord((1, 1).into(), SquareAxis::X.foward()),
+            Ok((2, 1).into())
+        );
+        assert_eq!(s.move_coord((2, 1).into(), SquareAxis::X.foward()), Err(()));
+        assert_eq!(s.move_coord((2, 1).into(), SquareAxis::Y.foward()), Err(()));
+        assert_eq!(
+            s.move_coord((0, 0).into(), SquareAxis::X.backward()),
+            Err(())
+        );
+        let far = SquareOffset::from((usize::MAX, usize::MAX));
+        assert_eq!(s.move_coord(far, SquareAxis::X.foward()), Err(()));
+        assert_eq!(s.move_coord(far, SquareAxis::Y.foward()), Err(()));
+        let s: SquareDiagonalShape = SquareDiagonalShape::new(3, 2);
+        assert_eq!(
+            s.move_coord((1, 0).into(), DirectedSquareDiagonalAxis::NE),
+            Ok((2, 1).into())
+        );
+        assert_eq!(
+            s.move_coord((2, 0).into(), DirectedSquareDiagonalAxis::NE),
+            Err(())
+        );
+        assert_eq!(s.move_coord(far, DirectedSquareDiagonalAxis::NE), Err(()));
+    }
+    #[test]
+    fn valid_directions() {
+        let s: SquareShape = SquareShape::new(3, 3);
+        let dirs = s.valid_directions((0, 0).into()).collect::<Vec<_>>();
+        assert_eq!(dirs, vec![SquareAxis::X.foward(), SquareAxis::Y.foward()]);
+        assert_eq!(s.valid_directions((1, 1).into()).count(), 4);
+        assert_eq!(s.valid_directions((2, 1).into()).count(), 3);
+        assert_eq!(s.valid_directions((3, 0).into()).count(), 0);
+        for d in s.valid_directions((2, 2).into()) {
+            assert!(s.move_coord((2, 2).into(), d).is_ok());
+        }
+        let s: SquareShape<Directed> = SquareShape::new(3, 3);
+        let dirs = s.valid_directions((2, 2).into()).collect::<Vec<_>>();
+        assert_eq!(dirs, vec![DirectedSquareAxis::RX, DirectedSquareAxis::RY]);
+    }
+    #[test]
+    fn to_node_index() {
+        let o = SquareOffset::from((3, 4));
+        let n: NodeIndex<u32> = o.to_node_index().unwrap();
+        assert_eq!(n, NodeIndex::new(3, 4));
+        assert_eq!(SquareOffset::from(n), o);
+        assert_eq!(o, n);
+        assert_eq!(n, o);
+        assert_ne!(o, NodeIndex::<u32>::new(4, 3));
+        let n: NodeIndex<usize> = o.into();
+        assert_eq!(n, o);
+
+        assert_eq!(
+            SquareOffset::from((255, 3)).to_node_index::<u8>(),
+            Some(NodeIndex::new(255, 3))
+        );
+        assert_eq!(SquareOffset::from((256, 3)).to_node_index::<u8>(), None);
+        assert_eq!(SquareOffset::from((3, 256)).to_node_index::<u8>(), None);
+        let big = SquareOffset::from((usize::MAX, 0));
+        assert_eq!(big.to_node_index::<u32>(), None);
+        assert_eq!(
+            big.to_node_index::<usize>(),
+            Some(NodeIndex::new(usize::MAX, 0))
+        );
+    }
+    #[test]
+    fn compact_index() {
+        assert_eq!(std::mem::size_of::<SquareOffset<u16>>(), 4);
+        assert_eq!(std::mem::size_of::<SquareOffset<u8>>(), 2);
+        assert_eq!(
+            std::mem::size_of::<SquareOffset>(),
+            2 * std::mem::size_of::<usize>()
+        );
+
+        type Compact<N, E> = LatticeGraph<N, E, SquareShape<Undirected, u16>>;
+        let fnode = |c: Offset| c.horizontal() * 10 + c.vertical();
+        let fedge = |c: Offset, a: SquareAxis| (fnode(c), a);
+        let compact = Compact::new_with(
+            SquareShape::new(4, 3),
+            |c| fnode(c.offset()),
+            |c, a| fedge(c.offset(), a),
+        );
+        let sq =
+            SquareGraph::new_with(SquareShape::new(4, 3), |c| fnode(c.0), |c, a| fedge(c.0, a));
+        assert_eq!(compact.node_count(), sq.node_count());
+        assert_eq!(
+            compact.edge_references().count(),
+            sq.edge_references().count()
+        );
+        for i in 0..sq.node_count() {
+            let (c, o) = (compact.from_index(i), sq.from_index(i));
+            assert_eq!(c.offset(), o.0);
+            assert_eq!(
+                c,
+                SquareOffset::<u16>::new(o.0.horizontal(), o.0.vertical())
+            );
+            assert_eq!(compact.node_weight(c), sq.node_weight(o));
+            assert!(compact
+                .edges(c)
+                .map(|e| (e.target().offset(), *e.weight()))
+                .eq(sq.edges(o).map(|e| (e.target().0, *e.weight()))));
+        }
+        assert_eq!(compact.node_weight(SquareOffset::new(4, 0)), None);
+
+        let directed =
+            LatticeGraph::<(), (), SquareShape<Directed, u8>>::new(SquareShape::new(256, 2));
+        let c = SquareOffset::<u8>::new(255, 0);
+        assert_eq!(directed.neighbors(c).count(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn compact_index_overflow() {
+        SquareShape::<Undirected, u8>::new(257, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the index type")]
+    fn compact_offset_new_overflow() {
+        SquareOffset::<u8>::new(300, 0);
+    }
+
+    #[test]
+    fn compact_offset_overflow() {
+        assert_eq!(SquareOffset::<u8>::try_new(300, 0), None);
+        assert_eq!(SquareOffset::<u8>::try_new(0, 256), None);
+        assert_eq!(
+            SquareOffset::<u8>::try_new(255, 3).map(|o| o.offset()),
+            Some(Offset::new(255, 3))
+        );
+        // it is not truncated into (44, 0).
+        let graph = LatticeGraph::<u8, (), SquareShape<Undirected, u8>>::new_with(
+            SquareShape::new(50, 1),
+            |c| c.offset().horizontal as u8,
+            |_, _| (),
+        );
+        assert_eq!(
+            SquareOffset::<u8>::try_new(300, 0).and_then(|c| graph.node_weight(c)),
+            None
+        );
+    }
+
+    #[test]
+    fn try_new() {
+        let graph = SquareGraph::<u8, u8>::try_new(SquareShape::new(3, 2)).unwrap();
+        assert_eq!(graph.node_count(), 6);
+        let graph = SquareGraph::<(), ()>::try_new(SquareShape::new(3, 0)).unwrap();
+        assert_eq!(graph.node_count(), 0);
+        assert_eq!(
+            SquareGraph::<u8, u8>::try_new(SquareShape::new(0, 2)).err(),
+            Some(GraphBuildError::ZeroHorizontal)
+        );
+        assert_eq!(
+            SquareGraph::<u8, u8>::try_new_with(SquareShape::new(0, 2), |_| 0, |_, _| 0).err(),
+            Some(GraphBuildError::ZeroHorizontal)
+        );
+        assert_eq!(
+            SquareGraph::<u8, u8>::try_new(SquareShape::new(usize::MAX, 2)).err(),
+            Some(GraphBuildError::Overflow {
+                horizontal: usize::MAX,
+                vertical: 2
+            })
+        );
+        assert_eq!(
+            SquareGraph::<u64, u8>::try_new(SquareShape::new(1 << 31, 1 << 30)).err(),
+            Some(GraphBuildError::Overflow {
+                horizontal: 1 << 31,
+                vertical: 1 << 30
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ZeroHorizontal")]
+    fn new_zero_horizontal() {
+        SquareGraph::<u8, u8>::new(SquareShape::new(0, 2));
+    }
+
+    #[test]
+    fn memory_usage() {
+        let graph = SquareGraph::<f32, i32>::new_with(SquareShape::new(10, 10), |_| 0., |_, _| 0);
+        // the values and the heads of the nodes and the edges of 2 axes.
+        let bytes = 3 * (10 * 10 * 4 + 10 * std::mem::size_of::<&[f32]>());
+        assert_eq!(graph.memory_usage(), bytes);
+        let directed =
+            LatticeGraph::<f32, i32, SquareShape<Directed>>::new(SquareShape::new(10, 10));
+        assert_eq!(directed.memory_usage(), bytes / 3 * 5);
+    }
 }
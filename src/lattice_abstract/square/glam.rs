@@ -0,0 +1,130 @@
+/*!
+Module to use [`glam::IVec2`] as Coordinate of Square Graph.
+
+The behavior is same as [`SquareShape`], but `x` is horizontal and `y` is vertical.
+Coordinates with negative value are treated as out of the graph.
+*/
+
+use super::*;
+use ::glam::IVec2;
+
+impl Coordinate for IVec2 {}
+
+/// Shape for Square Graph with [`IVec2`] as Coordinate.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IVec2SquareShape<E = Undirected>(SquareShape<E>);
+
+impl<E> IVec2SquareShape<E> {
+    /// Create a new shape.
+    pub fn new(h: usize, v: usize) -> Self {
+        Self(SquareShape::new(h, v))
+    }
+}
+
+/// Undirected Square Graph with [`IVec2`] as Coordinate.
+pub type IVec2SquareGraph<N, E> = LatticeGraph<N, E, IVec2SquareShape>;
+/// Directed Square Graph with [`IVec2`] as Coordinate.
+pub type IVec2DirectedSquareGraph<N, E> = LatticeGraph<N, E, IVec2SquareShape<Directed>>;
+
+#[inline]
+fn to_square(coord: IVec2) -> Result<SquareOffset, ()> {
+    if coord.x >= 0 && coord.y >= 0 {
+        Ok(SquareOffset(Offset::new(
+            coord.x as usize,
+            coord.y as usize,
+        )))
+    } else {
+        Err(())
+    }
+}
+
+#[inline]
+fn from_square(coord: SquareOffset) -> IVec2 {
+    IVec2::new(coord.0.horizontal as i32, coord.0.vertical as i32)
+}
+
+impl<E: Clone> Shape for IVec2SquareShape<E>
+where
+    SquareShape<E>: Shape<Coordinate = SquareOffset>,
+{
+    type Axis = <SquareShape<E> as Shape>::Axis;
+    type Coordinate = IVec2;
+    type OffsetConvertError = ();
+    type CoordinateMoveError = ();
+
+    #[inline]
+    fn horizontal(&self) -> usize {
+        self.0.horizontal()
+    }
+
+    #[inline]
+    fn vertical(&self) -> usize {
+        self.0.vertical()
+    }
+
+    #[inline]
+    fn to_offset(&self, coord: IVec2) -> Result<Offset, ()> {
+        self.0.to_offset(to_square(coord)?).map_err(|_| ())
+    }
+
+    #[inline]
+    unsafe fn to_offset_unchecked(&self, coord: IVec2) -> Offset {
+        Offset::new(coord.x as usize, coord.y as usize)
+    }
+
+    #[inline]
+    fn from_offset(&self, offset: Offset) -> IVec2 {
+        from_square(SquareOffset(offset))
+    }
+
+    fn move_coord(&self, coord: IVec2, dir: <Self::Axis as Axis>::Direction) -> Result<IVec2, ()> {
+        self.0
+            .move_coord(to_square(coord)?, dir)
+            .map(from_square)
+            .map_err(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::visit::*;
+
+    #[test]
+    fn neighbors() {
+        let graph =
+            IVec2SquareGraph::<IVec2, ()>::new_with(IVec2SquareShape::new(3, 5), |c| c, |_, _| ());
+        assert_eq!(graph.node_weight(IVec2::new(2, 4)), Some(&IVec2::new(2, 4)));
+        assert_eq!(graph.node_weight(IVec2::new(-1, 0)), None);
+        assert_eq!(graph.node_weight(IVec2::new(3, 0)), None);
+        assert!(graph
+            .neighbors(IVec2::new(0, 0))
+            .eq(IntoIterator::into_iter([
+                IVec2::new(1, 0),
+                IVec2::new(0, 1)
+            ])));
+        assert!(graph
+            .neighbors(IVec2::new(1, 2))
+            .eq(IntoIterator::into_iter([
+                IVec2::new(2, 2),
+                IVec2::new(1, 3),
+                IVec2::new(0, 2),
+                IVec2::new(1, 1)
+            ])));
+        assert_eq!(graph.edge_references().count(), 2 * 5 + 3 * 4);
+    }
+
+    #[test]
+    fn directed() {
+        let graph = IVec2DirectedSquareGraph::<(), i32>::new_with(
+            IVec2SquareShape::new(3, 3),
+            |_| (),
+            |c, a| c.x * 10 + c.y + a as i32 * 100,
+        );
+        assert_eq!(
+            graph.edge_weight((IVec2::new(1, 1), DirectedSquareAxis::RY)),
+            Some(&311)
+        );
+        assert_eq!(graph.neighbors(IVec2::new(2, 2)).count(), 2);
+    }
+}
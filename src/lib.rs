@@ -18,8 +18,37 @@ set default-features as false.
 Use [`hex2d`](`hex2d`) as a
 [`shapes::Coordinate`](`crate::lattice_abstract::shapes::Coordinate`).
 See [`hex::hex2d`] for details.
+
+## stable_graph
+Enable [`StableGraph`](`petgraph::stable_graph::StableGraph`) in petgraph to convert the lattice graph into it.
+See [`LatticeGraph::to_stable_graph`](`crate::lattice_abstract::LatticeGraph::to_stable_graph`).
+
+## glam
+Use [`glam::IVec2`](`glam::IVec2`) as a
+[`shapes::Coordinate`](`crate::lattice_abstract::shapes::Coordinate`) of square graph.
+See [`lattice_abstract::square::glam`] for details.
+
+## serde
+Implement `Serialize` and `Deserialize` of [`serde`](https://crates.io/crates/serde) for [`FixedVec2D`](`crate::fixedvec2d::FixedVec2D`)
+and [`LatticeGraph`](`crate::lattice_abstract::LatticeGraph`) with the shapes in this crate.
+
+## scene_json
+Export the graph as a json for visualization with
+[`LatticeGraph::to_scene_json`](`crate::lattice_abstract::LatticeGraph::to_scene_json`).
+This enables `serde` and uses [`serde_json`](https://crates.io/crates/serde_json).
+
+## bincode
+Save and load the graph in the compact binary format of [`bincode`](https://crates.io/crates/bincode)
+with [`LatticeGraph::to_bytes`](`crate::lattice_abstract::LatticeGraph::to_bytes`)
+and [`LatticeGraph::from_bytes`](`crate::lattice_abstract::LatticeGraph::from_bytes`).
+This enables `serde`.
+
+## rayon
+Iterate the nodes in parallel with [`rayon`](https://crates.io/crates/rayon)
+by `par_node_references` of [`LatticeGraph`](`crate::lattice_abstract::LatticeGraph`) and [`SquareGraph`].
 */
 
+pub mod algo;
 pub mod fixedvec2d;
 pub mod square;
 pub use square::SquareGraph;
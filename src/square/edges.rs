@@ -1,7 +1,7 @@
 use super::*;
 use petgraph::{
     graph::IndexType,
-    visit::{EdgeRef, IntoEdgeReferences, IntoEdges},
+    visit::{EdgeCount, EdgeRef, IntoEdgeReferences, IntoEdges},
 };
 use std::iter::FusedIterator;
 
@@ -19,6 +19,15 @@ where
     }
 }
 
+impl<N, E, Ix, S> EdgeCount for SquareGraph<N, E, Ix, S>
+where
+    Ix: IndexType,
+{
+    fn edge_count(&self) -> usize {
+        SquareGraph::edge_count(self)
+    }
+}
+
 /// Reference of Edge data (EdgeIndex, EdgeWeight, direction) in [`SquareGraph`].
 #[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct EdgeReference<'a, E, Ix: IndexType, S: Shape> {
@@ -23,6 +23,8 @@ mod neighbors;
 pub use neighbors::*;
 mod nodes;
 pub use nodes::*;
+mod subgrid;
+pub use subgrid::*;
 
 #[cfg(test)]
 mod tests;
@@ -118,6 +120,68 @@ impl Shape for HVLoop {
     }
 }
 
+/// [`Shape`] with horizontal and vertical axis swapped. Used in [`SquareGraph::rotate90`].
+pub trait SwapAxisShape: Shape {
+    /// Shape which loops in the axis swapped.
+    type Swapped: Shape;
+}
+impl SwapAxisShape for DefaultShape {
+    type Swapped = DefaultShape;
+}
+impl SwapAxisShape for HorizontalLoop {
+    type Swapped = VerticalLoop;
+}
+impl SwapAxisShape for VerticalLoop {
+    type Swapped = HorizontalLoop;
+}
+impl SwapAxisShape for HVLoop {
+    type Swapped = HVLoop;
+}
+
+/// Error of [`SquareGraph::from_arrays`] that the size of the arrays does not match the nodes, the [`Shape`] or the index type.
+/// The sizes are `(horizontal, vertical)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShapeMismatch {
+    /// The size of the nodes exceeds [`max_dimension`](`SquareGraph::max_dimension`) of the index type.
+    Nodes {
+        max: (usize, usize),
+        found: (usize, usize),
+    },
+    /// The size of the horizontal edges is wrong.
+    Horizontal {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    /// The size of the vertical edges is wrong.
+    Vertical {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+}
+
+impl std::fmt::Display for ShapeMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (axis, expected, found) = match self {
+            ShapeMismatch::Nodes { max, found } => {
+                return write!(
+                    f,
+                    "the size of the nodes is {}x{}, but the index type supports up to {}x{}",
+                    found.0, found.1, max.0, max.1
+                )
+            }
+            ShapeMismatch::Horizontal { expected, found } => ("horizontal", expected, found),
+            ShapeMismatch::Vertical { expected, found } => ("vertical", expected, found),
+        };
+        write!(
+            f,
+            "the size of the {} edges is {}x{}, but {}x{} is expected",
+            axis, found.0, found.1, expected.0, expected.1
+        )
+    }
+}
+
+impl std::error::Error for ShapeMismatch {}
+
 /// Undirected Square Grid Graph. It is has rectangle shape.
 /// ```text
 /// Node(i,j+1) - Edge(i,j+1,Horizontal) - Node(i+1,j+1)
@@ -162,6 +226,25 @@ where
         s
     }
 
+    /// Create a `SquareGraph` from the arrays of the nodes and edges.
+    /// The horizontal edges should be `(h - 1) x v` and the vertical edges should be `h x (v - 1)` for `h x v` nodes,
+    /// where the `- 1` is dropped in the axis that loops.
+    /// This is the safe version of [`new_raw`](`Self::new_raw`).
+    pub fn from_arrays(
+        nodes: FixedVec2D<N>,
+        horizontal: FixedVec2D<E>,
+        vertical: FixedVec2D<E>,
+    ) -> Result<Self, ShapeMismatch> {
+        Self::check_sizes(&nodes, &horizontal, &vertical)?;
+        Ok(unsafe { Self::new_raw(nodes, horizontal, vertical) })
+    }
+
+    /// Returns the maximum horizontal and vertical node count that the node index `Ix` can represent.
+    pub fn max_dimension() -> (usize, usize) {
+        let max = <Ix as IndexType>::max().index().saturating_add(1);
+        (max, max)
+    }
+
     /// Create a `SquareGraph` with the nodes and edges initialized with default.
     pub fn new(h: usize, v: usize) -> Self
     where
@@ -171,13 +254,40 @@ where
         Self::new_with(h, v, |_, _| N::default(), |_, _, _| E::default())
     }
 
+    /// Creates an empty `SquareGraph` without nodes and edges.
+    /// Both [`horizontal_node_count`](`Self::horizontal_node_count`) and
+    /// [`vertical_node_count`](`Self::vertical_node_count`) are 0.
+    /// No weight is allocated.
+    pub fn empty() -> Self {
+        fn empty<T>() -> FixedVec2D<T> {
+            unsafe { FixedVec2D::new_uninit(NonZeroUsize::new_unchecked(1), 0) }
+        }
+        unsafe { Self::new_raw(empty(), empty(), empty()) }
+    }
+
     /// Creates a `SquareGraph` with initializing nodes and edges from position.
+    /// If `h` or `v` is 0, returns the [`empty`](`Self::empty`) graph without calling `fnode` and `fedge`.
+    ///
+    /// # Panics
+    /// Panics if the size exceeds [`max_dimension`](`Self::max_dimension`) or `usize`.
     pub fn new_with<FN, FE>(h: usize, v: usize, mut fnode: FN, mut fedge: FE) -> Self
     where
         FN: FnMut(usize, usize) -> N,
         FE: FnMut(usize, usize, Axis) -> E,
     {
-        let nzh = NonZeroUsize::new(h).expect("h must be non zero");
+        let (max_h, max_v) = Self::max_dimension();
+        assert!(
+            h <= max_h && v <= max_v && h.checked_mul(v).is_some(),
+            "graph dimension {}x{} exceeds the limit {}x{} of the index type",
+            h,
+            v,
+            max_h,
+            max_v
+        );
+        let nzh = match NonZeroUsize::new(h) {
+            Some(nzh) if v != 0 => nzh,
+            _ => return Self::empty(),
+        };
         let mut nodes = unsafe { FixedVec2D::new_uninit(nzh, v) };
         let nodesref = nodes.mut_2d();
         let mh = if <S as Shape>::LOOP_HORIZONTAL {
@@ -232,14 +342,212 @@ where
         unsafe { Self::new_raw(nodes, horizontal, vertical) }
     }
 
+    /// Resize the graph, keeping the weights of the nodes and edges whose positions remain in the graph.
+    /// The new nodes and edges are initialized with `fnode` and `fedge` from the position like [`new_with`](`Self::new_with`).
+    /// The edges are kept by the position, so the edge that wrapped around the border of the loop
+    /// becomes the edge to the next node if the graph grows.
+    ///
+    /// # Panics
+    /// Panics in the same condition as [`new_with`](`Self::new_with`). The graph is left empty in that case.
+    pub fn resize_with<FN, FE>(&mut self, new_h: usize, new_v: usize, mut fnode: FN, mut fedge: FE)
+    where
+        FN: FnMut(usize, usize) -> N,
+        FE: FnMut(usize, usize, Axis) -> E,
+    {
+        struct Old<T> {
+            h: usize,
+            v: usize,
+            values: Vec<Option<T>>,
+        }
+        impl<T> Old<T> {
+            fn new(a: FixedVec2D<T>) -> Self {
+                Self {
+                    h: a.h_size(),
+                    v: a.v_size(),
+                    values: a.into_raw().into_iter().map(Some).collect(),
+                }
+            }
+            fn take(&mut self, h: usize, v: usize) -> Option<T> {
+                if h < self.h && v < self.v {
+                    self.values[h * self.v + v].take()
+                } else {
+                    None
+                }
+            }
+        }
+        let old = std::mem::replace(self, Self::empty());
+        let mut nodes = Old::new(old.nodes);
+        let mut horizontal = Old::new(old.horizontal);
+        let mut vertical = Old::new(old.vertical);
+        *self = Self::new_with(
+            new_h,
+            new_v,
+            |h, v| nodes.take(h, v).unwrap_or_else(|| fnode(h, v)),
+            |h, v, a| {
+                match a {
+                    Axis::Horizontal => horizontal.take(h, v),
+                    Axis::Vertical => vertical.take(h, v),
+                }
+                .unwrap_or_else(|| fedge(h, v, a))
+            },
+        );
+    }
+
+    /// Creates a `SquareGraph` with the other [`IndexType`], copying the weights.
+    ///
+    /// # Panics
+    /// Panics if the node index of this graph cannot be represented in `Ix2`.
+    pub fn reindex<Ix2: IndexType>(&self) -> SquareGraph<N, E, Ix2, S>
+    where
+        N: Clone,
+        E: Clone,
+    {
+        let (max_h, max_v) = SquareGraph::<N, E, Ix2, S>::max_dimension();
+        assert!(
+            self.horizontal_node_count() <= max_h && self.vertical_node_count() <= max_v,
+            "graph size does not fit in the index type"
+        );
+        unsafe {
+            SquareGraph::new_raw(
+                self.nodes.clone(),
+                self.horizontal.clone(),
+                self.vertical.clone(),
+            )
+        }
+    }
+
+    /// Creates a `SquareGraph` rotated 90 degrees counterclockwise, copying the weights.
+    /// The node at `(x, y)` moves to `(v - 1 - y, x)` where `v` is [`vertical_node_count`](`Self::vertical_node_count`),
+    /// and horizontal edges become vertical edges and vice versa.
+    ///
+    /// # Panics
+    /// Panics if the rotated graph has no horizontal edge array,
+    /// that is, `vertical_node_count()` is 0, or 1 without vertical loop.
+    pub fn rotate90(&self) -> SquareGraph<N, E, Ix, S::Swapped>
+    where
+        N: Clone,
+        E: Clone,
+        S: SwapAxisShape,
+    {
+        let h = self.horizontal_node_count();
+        let v = self.vertical_node_count();
+        let nodes = self.nodes.ref_2d();
+        let horizontal = self.horizontal.ref_2d();
+        let vertical = self.vertical.ref_2d();
+        let nv = NonZeroUsize::new(v).expect("vertical node count must be non zero");
+        let nodes = FixedVec2D::new(nv, h, |i, j| nodes[j][v - 1 - i].clone());
+        // old vertical edge at y is between y and y + 1, which is between `v - 2 - y` and `v - 1 - y` after rotation.
+        let nhe = NonZeroUsize::new(self.vertical.v_size())
+            .expect("vertical node count must be larger than 1 if it doesn't loop");
+        let new_horizontal =
+            FixedVec2D::new(nhe, h, |i, j| vertical[j][(2 * v - 2 - i) % v].clone());
+        let new_vertical = FixedVec2D::new(nv, self.horizontal.h_size(), |i, j| {
+            horizontal[j][v - 1 - i].clone()
+        });
+        unsafe { SquareGraph::new_raw(nodes, new_horizontal, new_vertical) }
+    }
+
+    /// Split the nodes into four quadrants, copying the weights.
+    /// The edges between the quadrants and the edges to loop are dropped.
+    ///
+    /// The nodes are split at `(h + 1) / 2` and `(v + 1) / 2`,
+    /// so the quadrants with lower indices get the extra row or column when the size is odd.
+    /// The quadrants are ordered as `[lower-left, lower-right, upper-left, upper-right]`,
+    /// where lower-left is the one with `(0, 0)`.
+    ///
+    /// # Panics
+    /// Panics if `horizontal_node_count()` is less than 4 or `vertical_node_count()` is less than 2,
+    /// as every quadrant needs a horizontal edge array.
+    pub fn quadrants(&self) -> [SquareGraph<N, E, Ix, DefaultShape>; 4]
+    where
+        N: Clone,
+        E: Clone,
+    {
+        let h = self.horizontal_node_count();
+        let v = self.vertical_node_count();
+        assert!(
+            h >= 4 && v >= 2,
+            "graph is too small to split into quadrants"
+        );
+        let (h0, v0) = (h - h / 2, v - v / 2);
+        let nodes = self.nodes.ref_2d();
+        let horizontal = self.horizontal.ref_2d();
+        let vertical = self.vertical.ref_2d();
+        let quadrant = |ho: usize, hs: usize, vo: usize, vs: usize| {
+            let nhs = NonZeroUsize::new(hs).unwrap();
+            let nodes = FixedVec2D::new(nhs, vs, |i, j| nodes[ho + i][vo + j].clone());
+            let horizontal = FixedVec2D::new(NonZeroUsize::new(hs - 1).unwrap(), vs, |i, j| {
+                horizontal[ho + i][vo + j].clone()
+            });
+            let vertical = FixedVec2D::new(nhs, vs - 1, |i, j| vertical[ho + i][vo + j].clone());
+            unsafe { SquareGraph::new_raw(nodes, horizontal, vertical) }
+        };
+        [
+            quadrant(0, h0, 0, v0),
+            quadrant(h0, h - h0, 0, v0),
+            quadrant(0, h0, v0, v - v0),
+            quadrant(h0, h - h0, v0, v - v0),
+        ]
+    }
+
     /// Check the size of nodes and edges.
     fn check_gen(&self) -> bool {
-        self.nodes.h_size()
-            == self.horizontal.h_size() + if <S as Shape>::LOOP_HORIZONTAL { 0 } else { 1 }
-            && self.nodes.v_size() == self.horizontal.v_size()
-            && self.nodes.h_size() == self.vertical.h_size()
-            && self.nodes.v_size()
-                == self.vertical.v_size() + if <S as Shape>::LOOP_VERTICAL { 0 } else { 1 }
+        Self::check_sizes(&self.nodes, &self.horizontal, &self.vertical).is_ok()
+    }
+
+    fn check_sizes(
+        nodes: &FixedVec2D<N>,
+        horizontal: &FixedVec2D<E>,
+        vertical: &FixedVec2D<E>,
+    ) -> Result<(), ShapeMismatch> {
+        let (h, v) = (nodes.h_size(), nodes.v_size());
+        let max = Self::max_dimension();
+        if h > max.0 || v > max.1 {
+            return Err(ShapeMismatch::Nodes { max, found: (h, v) });
+        }
+        if v == 0 {
+            // the graph without nodes, whose storage still has the non zero horizontal size.
+            let found = (horizontal.h_size(), horizontal.v_size());
+            if horizontal.size() != 0 {
+                return Err(ShapeMismatch::Horizontal {
+                    expected: (0, 0),
+                    found,
+                });
+            }
+            let found = (vertical.h_size(), vertical.v_size());
+            if vertical.size() != 0 {
+                return Err(ShapeMismatch::Vertical {
+                    expected: (0, 0),
+                    found,
+                });
+            }
+            return Ok(());
+        }
+        let expected = (
+            if <S as Shape>::LOOP_HORIZONTAL {
+                h
+            } else {
+                h - 1
+            },
+            v,
+        );
+        let found = (horizontal.h_size(), horizontal.v_size());
+        if expected != found {
+            return Err(ShapeMismatch::Horizontal { expected, found });
+        }
+        let expected = (
+            h,
+            if <S as Shape>::LOOP_VERTICAL {
+                v
+            } else {
+                v.saturating_sub(1)
+            },
+        );
+        let found = (vertical.h_size(), vertical.v_size());
+        if expected != found {
+            return Err(ShapeMismatch::Vertical { expected, found });
+        }
+        Ok(())
     }
 
     #[inline]
@@ -321,6 +629,71 @@ where
         Some(((x.0, x.1).into(), x.2))
     }
 
+    /// Get the neighbor of the node to the direction, with the flag whether it wraps around the border of loop.
+    /// Returns [`None`] if the node is not in the graph or there is no neighbor to the direction.
+    pub fn neighbor_in_dir(
+        &self,
+        node: NodeIndex<Ix>,
+        dir: SquareDirection,
+    ) -> Option<(NodeIndex<Ix>, bool)> {
+        let h = node.horizontal.index();
+        let v = node.vertical.index();
+        let hc = self.horizontal_node_count();
+        let vc = self.vertical_node_count();
+        if h >= hc || v >= vc {
+            return None;
+        }
+        let (h, v, wrap) = match dir {
+            SquareDirection::Foward(Axis::Horizontal) if h + 1 < hc => (h + 1, v, false),
+            SquareDirection::Foward(Axis::Horizontal) if <S as Shape>::LOOP_HORIZONTAL => {
+                (0, v, true)
+            }
+            SquareDirection::Backward(Axis::Horizontal) if h != 0 => (h - 1, v, false),
+            SquareDirection::Backward(Axis::Horizontal) if <S as Shape>::LOOP_HORIZONTAL => {
+                (hc - 1, v, true)
+            }
+            SquareDirection::Foward(Axis::Vertical) if v + 1 < vc => (h, v + 1, false),
+            SquareDirection::Foward(Axis::Vertical) if <S as Shape>::LOOP_VERTICAL => (h, 0, true),
+            SquareDirection::Backward(Axis::Vertical) if v != 0 => (h, v - 1, false),
+            SquareDirection::Backward(Axis::Vertical) if <S as Shape>::LOOP_VERTICAL => {
+                (h, vc - 1, true)
+            }
+            _ => return None,
+        };
+        Some((NodeIndex::new(Ix::new(h), Ix::new(v)), wrap))
+    }
+
+    /// Returns the count of the edges connected to the node, same as the count of [`neighbors`](`petgraph::visit::IntoNeighbors::neighbors`).
+    /// Returns 0 if the node is not in the graph.
+    pub fn degree(&self, node: NodeIndex<Ix>) -> usize {
+        [
+            SquareDirection::Backward(Axis::Horizontal),
+            SquareDirection::Foward(Axis::Horizontal),
+            SquareDirection::Backward(Axis::Vertical),
+            SquareDirection::Foward(Axis::Vertical),
+        ]
+        .iter()
+        .filter(|d| self.neighbor_in_dir(node, **d).is_some())
+        .count()
+    }
+
+    /// Check whether the node is on the border, that is, some direction from it is out of the graph.
+    /// No node is on the border for [`HVLoop`]. Returns `false` if the node is not in the graph.
+    pub fn is_border(&self, node: NodeIndex<Ix>) -> bool {
+        node.horizontal.index() < self.horizontal_node_count()
+            && node.vertical.index() < self.vertical_node_count()
+            && self.degree(node) < 4
+    }
+
+    /// Iterate the nodes on the border in the order of [`node_identifiers`](`IntoNodeIdentifiers::node_identifiers`).
+    /// See [`is_border`](`Self::is_border`).
+    pub fn border_nodes(&self) -> impl Iterator<Item = NodeIndex<Ix>> + '_ {
+        let v = self.vertical_node_count();
+        (0..self.node_count())
+            .map(move |i| NodeIndex::new(Ix::new(i / v), Ix::new(i % v)))
+            .filter(move |n| self.is_border(*n))
+    }
+
     #[inline]
     /// Get the edge reference form node.
     pub fn get_edge_reference<'a>(
@@ -351,9 +724,66 @@ impl<N, E, Ix, S> SquareGraph<N, E, Ix, S>
 where
     Ix: IndexType,
 {
+    /// Returns the count of the nodes. Same as [`NodeCount`](`petgraph::visit::NodeCount`).
+    pub fn node_count(&self) -> usize {
+        self.nodes.size()
+    }
+
+    /// Returns the count of the edges, including the edges wrapping around the loop.
+    /// Same as [`EdgeCount`](`petgraph::visit::EdgeCount`).
+    pub fn edge_count(&self) -> usize {
+        self.horizontal.size() + self.vertical.size()
+    }
+
+    /// Convert this graph into [`Graph`](`petgraph::graph::Graph`) of petgraph,
+    /// with the function to get the index of the node in it from [`NodeIndex`].
+    /// The edges wrapping around the loop are also added.
+    ///
+    /// # Panics
+    /// Panics if the node count exceeds the index type `u32` of the `Graph`.
+    pub fn to_petgraph(
+        &self,
+    ) -> (
+        petgraph::Graph<N, E, Undirected>,
+        impl Fn(NodeIndex<Ix>) -> petgraph::graph::NodeIndex,
+    )
+    where
+        N: Clone,
+        E: Clone,
+    {
+        use petgraph::graph::node_index;
+        let (hc, vc) = (self.horizontal_node_count(), self.vertical_node_count());
+        let index =
+            move |n: NodeIndex<Ix>| node_index(n.horizontal.index() * vc + n.vertical.index());
+        let mut g = petgraph::Graph::new_undirected();
+        g.reserve_exact_nodes(self.node_count());
+        g.reserve_exact_edges(self.edge_count());
+        for n in self.nodes.ref_1d() {
+            g.add_node(n.clone());
+        }
+        for (h, es) in self.horizontal.ref_2d().iter().enumerate() {
+            for (v, e) in es.iter().enumerate() {
+                let (s, t) = (h * vc + v, (h + 1) % hc * vc + v);
+                g.add_edge(node_index(s), node_index(t), e.clone());
+            }
+        }
+        for (h, es) in self.vertical.ref_2d().iter().enumerate() {
+            for (v, e) in es.iter().enumerate() {
+                let (s, t) = (h * vc + v, h * vc + (v + 1) % vc);
+                g.add_edge(node_index(s), node_index(t), e.clone());
+            }
+        }
+        (g, index)
+    }
+
     /// Returns the Node count in the horizontal direction.
+    /// It is 0 if the graph has no nodes.
     pub fn horizontal_node_count(&self) -> usize {
-        self.nodes.h_size()
+        if self.nodes.v_size() == 0 {
+            0
+        } else {
+            self.nodes.h_size()
+        }
     }
 
     /// Returns the Node count in the vertical direction.
@@ -366,6 +796,28 @@ where
         self.nodes.as_ref()
     }
 
+    /// Iterate the nodes in the row with the vertical index `v`, in the order of the horizontal index.
+    /// It is empty if `v` is out of the graph.
+    pub fn row(&self, v: usize) -> impl Iterator<Item = (NodeIndex<Ix>, &N)> + '_ {
+        let nodes = self.nodes();
+        let h = if v < self.vertical_node_count() {
+            nodes.len()
+        } else {
+            0
+        };
+        (0..h).map(move |h| (NodeIndex::new(Ix::new(h), Ix::new(v)), &nodes[h][v]))
+    }
+
+    /// Iterate the nodes in the column with the horizontal index `h`, in the order of the vertical index.
+    /// It is empty if `h` is out of the graph.
+    pub fn column(&self, h: usize) -> impl Iterator<Item = (NodeIndex<Ix>, &N)> + '_ {
+        self.nodes().get(h).into_iter().flat_map(move |c| {
+            c.iter()
+                .enumerate()
+                .map(move |(v, n)| (NodeIndex::new(Ix::new(h), Ix::new(v)), n))
+        })
+    }
+
     /// Get a reference to the horizontal edges. `[horizontal][vertical]`
     pub fn horizontal(&self) -> &[&[E]] {
         self.horizontal.as_ref()
@@ -376,6 +828,24 @@ where
         self.vertical.as_ref()
     }
 
+    /// Iterate the horizontal edges with its source node, in the order of `[horizontal][vertical]`.
+    /// The edges wrapping around the loop are included if the shape loops horizontally.
+    pub fn horizontal_edges(&self) -> impl Iterator<Item = (NodeIndex<Ix>, &E)> + '_ {
+        edges_with_source(self.horizontal())
+    }
+
+    /// Iterate the vertical edges with its source node, in the order of `[horizontal][vertical]`.
+    /// The edges wrapping around the loop are included if the shape loops vertically.
+    pub fn vertical_edges(&self) -> impl Iterator<Item = (NodeIndex<Ix>, &E)> + '_ {
+        edges_with_source(self.vertical())
+    }
+
+    /// Returns the bytes allocated for the node and edge arrays.
+    /// See [`FixedVec2D::memory_usage`].
+    pub fn memory_usage(&self) -> usize {
+        self.nodes.memory_usage() + self.horizontal.memory_usage() + self.vertical.memory_usage()
+    }
+
     /// Get a mutable reference to the nodes. `[horizontal][vertical]`
     pub fn nodes_mut(&mut self) -> &mut [&mut [N]] {
         self.nodes.as_mut()
@@ -390,6 +860,198 @@ where
     pub fn vertical_mut(&mut self) -> &[&mut [E]] {
         self.vertical.as_mut()
     }
+
+    /// Apply `f` to every node weight with its position `(h, v)`,
+    /// in the storage order `[horizontal][vertical]`.
+    pub fn map_nodes_in_place<F: FnMut(usize, usize, &mut N)>(&mut self, mut f: F) {
+        for (hi, nv) in self.nodes.mut_2d().iter_mut().enumerate() {
+            for (vi, n) in nv.iter_mut().enumerate() {
+                f(hi, vi, n);
+            }
+        }
+    }
+
+    /// Apply `f` to every edge weight with its position `(h, v)` and [`Axis`].
+    /// The horizontal edges are visited first and then the vertical edges,
+    /// each in the storage order `[horizontal][vertical]`.
+    pub fn map_edges_in_place<F: FnMut(usize, usize, Axis, &mut E)>(&mut self, mut f: F) {
+        for (edges, axis) in [
+            (&mut self.horizontal, Axis::Horizontal),
+            (&mut self.vertical, Axis::Vertical),
+        ] {
+            for (hi, ev) in edges.mut_2d().iter_mut().enumerate() {
+                for (vi, e) in ev.iter_mut().enumerate() {
+                    f(hi, vi, axis, e);
+                }
+            }
+        }
+    }
+
+    /// Call `f` with the mutable references of the weights of the neighbors of the node `c`,
+    /// in the order of [`neighbors`](`petgraph::visit::IntoNeighbors::neighbors`).
+    /// A node which is the neighbor in more than one direction (e.g. a loop of size 2) is passed only once,
+    /// so the references never alias.
+    ///
+    /// # Panics
+    /// Panics if the node is not in the graph.
+    pub fn with_neighbor_weights_mut<F: FnOnce(&mut [&mut N])>(&mut self, c: NodeIndex<Ix>, f: F)
+    where
+        S: Shape,
+    {
+        let v = self.vertical_node_count();
+        assert!(
+            c.horizontal.index() < self.horizontal_node_count() && c.vertical.index() < v,
+            "node is not in the graph"
+        );
+        let mut ids = [0; 4];
+        let mut len = 0;
+        for dir in [
+            SquareDirection::Backward(Axis::Horizontal),
+            SquareDirection::Foward(Axis::Horizontal),
+            SquareDirection::Backward(Axis::Vertical),
+            SquareDirection::Foward(Axis::Vertical),
+        ] {
+            let n = match self.neighbor_in_dir(c, dir) {
+                Some((n, _)) => n,
+                None => continue,
+            };
+            let i = n.horizontal.index() * v + n.vertical.index();
+            if !ids[..len].contains(&i) {
+                ids[len] = i;
+                len += 1;
+            }
+        }
+        // split the nodes in the order of the index and put them back to the order of neighbors.
+        let mut order = [0, 1, 2, 3];
+        let order = &mut order[..len];
+        order.sort_unstable_by_key(|&k| ids[k]);
+        let mut slots: [Option<&mut N>; 4] = [None, None, None, None];
+        let mut rest = self.nodes.mut_1d();
+        let mut offset = 0;
+        for &k in order.iter() {
+            let (n, tail) = std::mem::take(&mut rest)[ids[k] - offset..]
+                .split_first_mut()
+                .unwrap();
+            slots[k] = Some(n);
+            rest = tail;
+            offset = ids[k] + 1;
+        }
+        let mut refs = IntoIterator::into_iter(slots).flatten().collect::<Vec<_>>();
+        f(&mut refs);
+    }
+
+    /**
+    Render the nodes as a grid of characters for debugging.
+    Each line is a row of the same vertical index and each character is a node with horizontal index.
+    `(0, 0)` is placed at the bottom left, as `up` is the forward direction of vertical axis.
+    ```
+    # use lattice_graph::SquareGraph;
+    let graph = SquareGraph::<_, (), u32>::new_with(3, 2, |h, v| h + v, |_, _, _| ());
+    assert_eq!(graph.debug_grid(|n| if *n == 0 { '#' } else { '.' }), "...\n#..\n");
+    ```
+    */
+    pub fn debug_grid(&self, cell: impl Fn(&N) -> char) -> String {
+        let h = self.horizontal_node_count();
+        let v = self.vertical_node_count();
+        let nodes = self.nodes.ref_2d();
+        let mut s = String::with_capacity((h + 1) * v);
+        for vi in (0..v).rev() {
+            for n in nodes.iter() {
+                s.push(cell(&n[vi]));
+            }
+            s.push('\n');
+        }
+        s
+    }
+
+    /// Split the nodes into two non-overlapping mutable strips of columns at horizontal index `at`,
+    /// so that each strip can be updated by a different thread.
+    /// The first strip has the columns with horizontal index in `0..at` and the second has the rest.
+    /// The edges can only be read from each strip.
+    ///
+    /// # Panics
+    /// Panics if `at > horizontal_node_count()`.
+    pub fn split_columns(
+        &mut self,
+        at: usize,
+    ) -> (NodesStripMut<'_, N, E>, NodesStripMut<'_, N, E>) {
+        let (lo, hi) = self.nodes.mut_2d().split_at_mut(at);
+        let horizontal = self.horizontal.ref_2d();
+        let vertical = self.vertical.ref_2d();
+        (
+            NodesStripMut {
+                nodes: lo,
+                offset: 0,
+                horizontal,
+                vertical,
+            },
+            NodesStripMut {
+                nodes: hi,
+                offset: at,
+                horizontal,
+                vertical,
+            },
+        )
+    }
+}
+
+/// Mutable strip of the columns of [`SquareGraph`]. See [`split_columns`](`SquareGraph::split_columns`).
+#[derive(Debug)]
+pub struct NodesStripMut<'a, N, E> {
+    nodes: &'a mut [&'a mut [N]],
+    offset: usize,
+    horizontal: &'a [&'a [E]],
+    vertical: &'a [&'a [E]],
+}
+
+impl<'a, N, E> NodesStripMut<'a, N, E> {
+    /// Returns the horizontal index of the first column in this strip.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the horizontal indices of the columns in this strip.
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.nodes.len()
+    }
+
+    /// Get a reference to the nodes in this strip. `[horizontal - offset][vertical]`
+    pub fn nodes(&self) -> &[&mut [N]] {
+        self.nodes
+    }
+
+    /// Get a mutable reference to the nodes in this strip. `[horizontal - offset][vertical]`
+    pub fn nodes_mut(&mut self) -> &mut [&'a mut [N]] {
+        self.nodes
+    }
+
+    /// Get a mutable reference to the node weight with the index of the whole graph.
+    /// Returns [`None`] if the node is not in this strip.
+    pub fn node_weight_mut<Ix: IndexType>(&mut self, id: NodeIndex<Ix>) -> Option<&mut N> {
+        self.nodes
+            .get_mut(id.horizontal.index().checked_sub(self.offset)?)?
+            .get_mut(id.vertical.index())
+    }
+
+    /// Get a reference to all the horizontal edges of the graph. `[horizontal][vertical]`
+    pub fn horizontal(&self) -> &'a [&'a [E]] {
+        self.horizontal
+    }
+
+    /// Get a reference to all the vertical edges of the graph. `[horizontal][vertical]`
+    pub fn vertical(&self) -> &'a [&'a [E]] {
+        self.vertical
+    }
+}
+
+fn edges_with_source<'a, E, Ix: IndexType>(
+    edges: &'a [&'a [E]],
+) -> impl Iterator<Item = (NodeIndex<Ix>, &'a E)> {
+    edges.iter().enumerate().flat_map(|(h, es)| {
+        es.iter()
+            .enumerate()
+            .map(move |(v, e)| (NodeIndex::new(Ix::new(h), Ix::new(v)), e))
+    })
 }
 
 impl<E, Ix, S> SquareGraph<(), E, Ix, S>
@@ -406,6 +1068,40 @@ where
     }
 }
 
+impl<N, Ix, S> SquareGraph<N, bool, Ix, S>
+where
+    Ix: IndexType,
+    S: Shape,
+{
+    /**
+    Create a `SquareGraph` whose edge weights tell whether it can move between the nodes.
+    `can_move` is called once for each edge with the positions of the nodes at the both ends,
+    the one with the smaller index (or the one before looping) first.
+    ```
+    # use lattice_graph::SquareGraph;
+    # use petgraph::data::DataMap;
+    # use lattice_graph::square::Axis;
+    let graph = SquareGraph::<_, _, u32>::walkable_from(3, 1, |h, _| h, |a, b| a.0 + b.0 < 3);
+    assert_eq!(graph.edge_weight(((0, 0).into(), Axis::Horizontal).into()), Some(&true));
+    assert_eq!(graph.edge_weight(((1, 0).into(), Axis::Horizontal).into()), Some(&false));
+    ```
+    */
+    pub fn walkable_from(
+        h: usize,
+        v: usize,
+        terrain: impl Fn(usize, usize) -> N,
+        can_move: impl Fn((usize, usize), (usize, usize)) -> bool,
+    ) -> Self {
+        Self::new_with(h, v, terrain, |hi, vi, axis| {
+            let target = match axis {
+                Axis::Horizontal => ((hi + 1) % h, vi),
+                Axis::Vertical => (hi, (vi + 1) % v),
+            };
+            can_move((hi, vi), target)
+        })
+    }
+}
+
 impl<N, E, Ix, S> GraphBase for SquareGraph<N, E, Ix, S>
 where
     Ix: IndexType,
@@ -487,6 +1183,18 @@ impl VisMap {
         }
         Self { v: vec }
     }
+
+    /// Clear the map and resize it to `h` x `v`, since it may be created by the graph of another size.
+    fn reset(&mut self, h: usize, v: usize) {
+        self.v.resize_with(h, || FixedBitSet::with_capacity(v));
+        for x in self.v.iter_mut() {
+            if x.len() == v {
+                x.clear();
+            } else {
+                *x = FixedBitSet::with_capacity(v);
+            }
+        }
+    }
 }
 
 impl<Ix: IndexType> VisitMap<NodeIndex<Ix>> for VisMap {
@@ -510,6 +1218,6 @@ where
     }
 
     fn reset_map(self: &Self, map: &mut Self::Map) {
-        map.v.iter_mut().for_each(|x| x.clear())
+        map.reset(self.horizontal_node_count(), self.vertical_node_count())
     }
 }
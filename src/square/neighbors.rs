@@ -90,6 +90,8 @@ where
     }
 }
 
+/// As the graph is undirected, the direction is ignored and the neighbors are same as [`neighbors`](`IntoNeighbors::neighbors`),
+/// including the ones across the loop.
 impl<'a, N, E, Ix, S> IntoNeighborsDirected for &'a SquareGraph<N, E, Ix, S>
 where
     Ix: IndexType,
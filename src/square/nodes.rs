@@ -136,7 +136,7 @@ where
     Ix: IndexType,
 {
     fn node_count(self: &Self) -> usize {
-        self.nodes.size()
+        SquareGraph::node_count(self)
     }
 }
 
@@ -157,3 +157,25 @@ where
         (i / h, i % h).into()
     }
 }
+
+#[cfg(feature = "rayon")]
+impl<N, E, Ix, S> SquareGraph<N, E, Ix, S>
+where
+    Ix: IndexType + Send,
+{
+    /// Parallel version of [`node_references`](`IntoNodeReferences::node_references`), in the same order.
+    pub fn par_node_references(
+        &self,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = (NodeIndex<Ix>, &N)>
+    where
+        N: Sync,
+    {
+        use rayon::prelude::*;
+        let v = self.vertical_node_count();
+        self.nodes
+            .ref_1d()
+            .par_iter()
+            .enumerate()
+            .map(move |(i, n)| (NodeIndex::new(Ix::new(i / v), Ix::new(i % v)), n))
+    }
+}
@@ -0,0 +1,247 @@
+use super::*;
+use petgraph::{
+    graph::IndexType,
+    visit::{IntoNeighbors, IntoNodeIdentifiers},
+};
+
+/// View of a rectangle region of [`SquareGraph`] as a graph, without copying.
+/// The node and edge ids are same as the parent graph,
+/// and the nodes and edges outside of the region are not in the graph even if they exist in the parent.
+/// See [`SquareGraph::subgrid`].
+#[derive(Debug)]
+pub struct SubGrid<'a, N, E, Ix: IndexType = usize, S = DefaultShape> {
+    graph: &'a SquareGraph<N, E, Ix, S>,
+    h: Range<usize>,
+    v: Range<usize>,
+}
+
+impl<'a, N, E, Ix: IndexType, S> Clone for SubGrid<'a, N, E, Ix, S> {
+    fn clone(&self) -> Self {
+        Self {
+            graph: self.graph,
+            h: self.h.clone(),
+            v: self.v.clone(),
+        }
+    }
+}
+
+impl<N, E, Ix, S> SquareGraph<N, E, Ix, S>
+where
+    Ix: IndexType,
+{
+    /// Creates a [`SubGrid`] view of the region starting from `origin` with the size `horizontal x vertical`.
+    /// Returns [`None`] if the region is not inside of the graph.
+    pub fn subgrid(
+        &self,
+        origin: NodeIndex<Ix>,
+        horizontal: usize,
+        vertical: usize,
+    ) -> Option<SubGrid<'_, N, E, Ix, S>> {
+        let (h, v) = (origin.horizontal.index(), origin.vertical.index());
+        if h.checked_add(horizontal)? > self.horizontal_node_count()
+            || v.checked_add(vertical)? > self.vertical_node_count()
+        {
+            return None;
+        }
+        Some(SubGrid {
+            graph: self,
+            h: h..h + horizontal,
+            v: v..v + vertical,
+        })
+    }
+}
+
+impl<'a, N, E, Ix, S> SubGrid<'a, N, E, Ix, S>
+where
+    Ix: IndexType,
+{
+    /// Get a reference to the parent graph.
+    pub fn graph(&self) -> &'a SquareGraph<N, E, Ix, S> {
+        self.graph
+    }
+
+    /// Returns the index of the node at the bottom left of the region in the parent graph.
+    pub fn origin(&self) -> NodeIndex<Ix> {
+        NodeIndex::new(Ix::new(self.h.start), Ix::new(self.v.start))
+    }
+
+    /// Returns the node count in the horizontal direction.
+    pub fn horizontal_node_count(&self) -> usize {
+        self.h.len()
+    }
+
+    /// Returns the node count in the vertical direction.
+    pub fn vertical_node_count(&self) -> usize {
+        self.v.len()
+    }
+
+    /// Check whether the node is in the region.
+    pub fn contains(&self, n: NodeIndex<Ix>) -> bool {
+        self.h.contains(&n.horizontal.index()) && self.v.contains(&n.vertical.index())
+    }
+
+    /// Returns the node the edge goes to, which might be wrapped around the loop of the parent.
+    fn edge_target(&self, e: EdgeIndex<Ix>) -> NodeIndex<Ix> {
+        let (h, v) = (e.node.horizontal.index(), e.node.vertical.index());
+        match e.axis {
+            Axis::Horizontal => ((h + 1) % self.graph.horizontal_node_count(), v),
+            Axis::Vertical => (h, (v + 1) % self.graph.vertical_node_count()),
+        }
+        .into()
+    }
+}
+
+impl<'a, N, E, Ix, S> GraphBase for SubGrid<'a, N, E, Ix, S>
+where
+    Ix: IndexType,
+{
+    type NodeId = NodeIndex<Ix>;
+    type EdgeId = EdgeIndex<Ix>;
+}
+
+impl<'a, N, E, Ix, S> Data for SubGrid<'a, N, E, Ix, S>
+where
+    Ix: IndexType,
+{
+    type NodeWeight = N;
+    type EdgeWeight = E;
+}
+
+impl<'a, N, E, Ix, S> DataMap for SubGrid<'a, N, E, Ix, S>
+where
+    Ix: IndexType,
+{
+    fn node_weight(&self, id: Self::NodeId) -> Option<&Self::NodeWeight> {
+        if self.contains(id) {
+            self.graph.node_weight(id)
+        } else {
+            None
+        }
+    }
+
+    fn edge_weight(&self, id: Self::EdgeId) -> Option<&Self::EdgeWeight> {
+        if self.contains(id.node) && self.contains(self.edge_target(id)) {
+            self.graph.edge_weight(id)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, N, E, Ix, S> Visitable for SubGrid<'a, N, E, Ix, S>
+where
+    Ix: IndexType,
+{
+    type Map = VisMap;
+
+    fn visit_map(&self) -> Self::Map {
+        self.graph.visit_map()
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        self.graph.reset_map(map)
+    }
+}
+
+impl<'a, 'b, N, E, Ix, S> IntoNeighbors for &'b SubGrid<'a, N, E, Ix, S>
+where
+    Ix: IndexType,
+    S: Shape,
+{
+    type Neighbors = SubGridNeighbors<Ix, S>;
+
+    fn neighbors(self, a: Self::NodeId) -> Self::Neighbors {
+        SubGridNeighbors {
+            neighbors: self.graph.neighbors(a),
+            h: self.h.clone(),
+            v: if self.contains(a) {
+                self.v.clone()
+            } else {
+                0..0
+            },
+        }
+    }
+}
+
+/// Neighbors of the node in [`SubGrid`]. See [`neighbors`](`IntoNeighbors::neighbors`).
+#[derive(Clone, Debug)]
+pub struct SubGridNeighbors<Ix: IndexType, S> {
+    neighbors: Neighbors<Ix, S>,
+    h: Range<usize>,
+    v: Range<usize>,
+}
+
+impl<Ix, S> Iterator for SubGridNeighbors<Ix, S>
+where
+    Ix: IndexType,
+    S: Shape,
+{
+    type Item = NodeIndex<Ix>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.v.is_empty() {
+            return None;
+        }
+        let (h, v) = (&self.h, &self.v);
+        self.neighbors
+            .find(|n| h.contains(&n.horizontal.index()) && v.contains(&n.vertical.index()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.neighbors.size_hint().1)
+    }
+}
+
+impl<Ix, S> FusedIterator for SubGridNeighbors<Ix, S>
+where
+    Ix: IndexType,
+    S: Shape,
+{
+}
+
+impl<'a, 'b, N, E, Ix, S> IntoNodeIdentifiers for &'b SubGrid<'a, N, E, Ix, S>
+where
+    Ix: IndexType,
+{
+    type NodeIdentifiers = SubGridNodeIndices<Ix>;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        SubGridNodeIndices {
+            // `NodeIndices` assumes the horizontal size is not 0.
+            indices: if self.h.is_empty() {
+                NodeIndices::new(0, 0)
+            } else {
+                NodeIndices::new(self.h.len(), self.v.len())
+            },
+            h: self.h.start,
+            v: self.v.start,
+        }
+    }
+}
+
+/// Iterate all index of [`SubGrid`]. See [`node_identifiers`](`IntoNodeIdentifiers::node_identifiers`).
+#[derive(Clone, Debug)]
+pub struct SubGridNodeIndices<Ix> {
+    indices: NodeIndices<Ix>,
+    h: usize,
+    v: usize,
+}
+
+impl<Ix: IndexType> Iterator for SubGridNodeIndices<Ix> {
+    type Item = NodeIndex<Ix>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.indices.next()?;
+        Some(NodeIndex::new(
+            Ix::new(n.horizontal.index() + self.h),
+            Ix::new(n.vertical.index() + self.v),
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<Ix: IndexType> FusedIterator for SubGridNodeIndices<Ix> {}
+impl<Ix: IndexType> ExactSizeIterator for SubGridNodeIndices<Ix> {}
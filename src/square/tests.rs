@@ -195,6 +195,15 @@ fn neighbors() {
 
     let v12 = sq.neighbors((1, 2).into());
     debug_assert!(v12.eq(IntoIter::new([(0, 2), (2, 2), (1, 1), (1, 3)])));
+
+    let n = |h, v| NodeIndex::new(h, v);
+    assert_eq!(sq.degree(n(0, 0)), 2);
+    assert_eq!(sq.degree(n(2, 1)), 3);
+    assert_eq!(sq.degree(n(1, 2)), 4);
+    assert_eq!(sq.degree(n(3, 0)), 0);
+    for c in sq.node_identifiers() {
+        assert_eq!(sq.degree(c), sq.neighbors(c).count());
+    }
 }
 
 #[test]
@@ -220,6 +229,28 @@ fn neighbors_hv() {
 
     let v12 = sq.neighbors((1, 2).into());
     debug_assert!(v12.eq(IntoIter::new([(0, 2), (2, 2), (1, 1), (1, 3)])));
+
+    assert!(sq.node_identifiers().all(|c| sq.degree(c) == 4));
+}
+
+#[test]
+fn neighbors_directed() {
+    use petgraph::Direction::{Incoming, Outgoing};
+    fn corner<S: Shape>() -> [Vec<NodeIndex<u32>>; 2] {
+        let sq = SquareGraph::<(), (), u32, S>::new(3, 5);
+        let n = NodeIndex::new(0, 0);
+        let out = sq.neighbors_directed(n, Outgoing).collect::<Vec<_>>();
+        let inc = sq.neighbors_directed(n, Incoming).collect::<Vec<_>>();
+        assert!(sq.neighbors(n).eq(out.iter().copied()));
+        [out, inc]
+    }
+    let n = |h: u32, v: u32| NodeIndex::new(h, v);
+    let expected = vec![n(1, 0), n(0, 1)];
+    assert_eq!(corner::<DefaultShape>(), [expected.clone(), expected]);
+    let expected = vec![n(2, 0), n(1, 0), n(0, 1)];
+    assert_eq!(corner::<HorizontalLoop>(), [expected.clone(), expected]);
+    let expected = vec![n(2, 0), n(1, 0), n(0, 4), n(0, 1)];
+    assert_eq!(corner::<HVLoop>(), [expected.clone(), expected]);
 }
 
 #[test]
@@ -381,3 +412,767 @@ fn astar() {
     assert_eq!(d, 5);
     assert_eq!(p, [(2, 1), (1, 1), (0, 1), (0, 0)])
 }
+
+#[test]
+fn split_columns() {
+    let mut sq = SquareGraph::<_, _, u32>::new_with(
+        5,
+        3,
+        |x, y| x + 2 * y,
+        |x, y, d| x + 2 * y + if d.is_horizontal() { 0 } else { 10 },
+    );
+    let (mut lo, mut hi) = sq.split_columns(2);
+    assert_eq!(lo.range(), 0..2);
+    assert_eq!(hi.range(), 2..5);
+    assert_eq!(lo.node_weight_mut(NodeIndex::new(2u32, 0)), None);
+    assert_eq!(hi.node_weight_mut(NodeIndex::new(2u32, 0)), Some(&mut 2));
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            let h = lo.horizontal();
+            for (i, n) in lo.nodes_mut().iter_mut().enumerate() {
+                for (j, w) in n.iter_mut().enumerate() {
+                    *w += 100 * h[i][j];
+                }
+            }
+        });
+        s.spawn(|| {
+            let offset = hi.offset();
+            let v = hi.vertical();
+            for (i, n) in hi.nodes_mut().iter_mut().enumerate() {
+                for (j, w) in n.iter_mut().enumerate() {
+                    *w += 1000 * v[i + offset].get(j).unwrap_or(&0);
+                }
+            }
+        });
+    });
+    for x in 0..5 {
+        for y in 0..3 {
+            let base = x + 2 * y;
+            let expected = if x < 2 {
+                base + 100 * base
+            } else if y < 2 {
+                base + 1000 * (base + 10)
+            } else {
+                base
+            };
+            assert_eq!(sq.node_weight((x, y).into()), Some(&expected));
+        }
+    }
+}
+
+#[test]
+fn debug_grid() {
+    let sq = SquareGraph::<_, (), u32>::new_with(3, 2, |x, y| (x, y), |_, _, _| ());
+    let grid = sq.debug_grid(|&(x, y)| match (x, y) {
+        (0, 0) => 'o',
+        (_, 0) => '-',
+        (2, _) => '#',
+        _ => '.',
+    });
+    assert_eq!(grid, "..#\no--\n");
+}
+
+#[test]
+fn reindex() {
+    let sq = SquareGraph::<_, _, u32>::new_with(
+        4,
+        3,
+        |x, y| x + 2 * y,
+        |x, y, d| (x + 2 * y) as i32 * (if d.is_horizontal() { 1 } else { -1 }),
+    );
+    let sq16 = sq.reindex::<u16>();
+    assert_eq!(sq16.node_weight(NodeIndex::new(3u16, 2)), Some(&7));
+    assert_eq!(
+        sq16.edge_weight((NodeIndex::new(1u16, 1), Axis::Vertical).into()),
+        Some(&-3)
+    );
+    let sq32 = sq16.reindex::<u32>();
+    assert_eq!(sq32.nodes(), sq.nodes());
+    assert_eq!(sq32.horizontal(), sq.horizontal());
+    assert_eq!(sq32.vertical(), sq.vertical());
+}
+
+#[test]
+#[should_panic]
+fn reindex_overflow() {
+    let sq = SquareGraph::<(), (), u32>::new(300, 2);
+    sq.reindex::<u8>();
+}
+
+#[test]
+fn rotate90() {
+    let sq = SquareGraph::<_, _, u32>::new_with(4, 3, |x, y| (x, y), |x, y, d| (x, y, d));
+    let rot = sq.rotate90();
+    assert_eq!(rot.horizontal_node_count(), 3);
+    assert_eq!(rot.vertical_node_count(), 4);
+    for x in 0..4 {
+        for y in 0..3 {
+            let n = NodeIndex::<u32>::from((2 - y, x));
+            assert_eq!(rot.node_weight(n), Some(&(x, y)));
+        }
+    }
+    // horizontal edge (x, y) - (x + 1, y) becomes (2 - y, x) - (2 - y, x + 1)
+    assert_eq!(
+        rot.edge_weight((NodeIndex::new(2, 0), Axis::Vertical).into()),
+        Some(&(0, 0, Axis::Horizontal))
+    );
+    // vertical edge (x, y) - (x, y + 1) becomes (2 - y, x) - (1 - y, x)
+    assert_eq!(
+        rot.edge_weight((NodeIndex::new(1, 3), Axis::Horizontal).into()),
+        Some(&(3, 0, Axis::Vertical))
+    );
+    for e in sq.edge_references() {
+        let (s, t) = (e.source(), e.target());
+        let rs = NodeIndex::<u32>::new(2 - s.vertical, s.horizontal);
+        let rt = NodeIndex::<u32>::new(2 - t.vertical, t.horizontal);
+        assert!(rot
+            .edges(rs)
+            .any(|re| re.target() == rt && re.weight() == e.weight()));
+    }
+    assert_eq!(sq.edge_references().count(), rot.edge_references().count());
+}
+
+#[test]
+fn rotate90_loop() {
+    let sq =
+        SquareGraph::<_, _, u32, VerticalLoop>::new_with(3, 4, |x, y| (x, y), |x, y, d| (x, y, d));
+    let rot: SquareGraph<_, _, u32, HorizontalLoop> = sq.rotate90();
+    // vertical edge (x, 3) - (x, 0) becomes the horizontal edge (3, x) - (0, x)
+    assert_eq!(
+        rot.edge_weight((NodeIndex::new(3, 1), Axis::Horizontal).into()),
+        Some(&(1, 3, Axis::Vertical))
+    );
+    let back = rot.rotate90().rotate90().rotate90();
+    assert_eq!(back.nodes(), sq.nodes());
+    assert_eq!(back.horizontal(), sq.horizontal());
+    assert_eq!(back.vertical(), sq.vertical());
+}
+
+#[test]
+fn neighbor_in_dir_hv() {
+    let sq = SquareGraph::<(), (), u32, HVLoop>::new(3, 4);
+    let n = |h: u32, v: u32| NodeIndex::new(h, v);
+    let d = SquareDirection::right();
+    assert_eq!(sq.neighbor_in_dir(n(1, 1), d), Some((n(2, 1), false)));
+    assert_eq!(sq.neighbor_in_dir(n(2, 1), d), Some((n(0, 1), true)));
+    assert_eq!(
+        sq.neighbor_in_dir(n(0, 3), SquareDirection::left()),
+        Some((n(2, 3), true))
+    );
+    assert_eq!(
+        sq.neighbor_in_dir(n(0, 3), SquareDirection::up()),
+        Some((n(0, 0), true))
+    );
+    assert_eq!(
+        sq.neighbor_in_dir(n(0, 0), SquareDirection::down()),
+        Some((n(0, 3), true))
+    );
+    assert_eq!(
+        sq.neighbor_in_dir(n(0, 1), SquareDirection::down()),
+        Some((n(0, 0), false))
+    );
+    assert_eq!(sq.neighbor_in_dir(n(3, 0), d), None);
+
+    let sq = SquareGraph::<(), (), u32>::new(3, 4);
+    assert_eq!(sq.neighbor_in_dir(n(2, 1), d), None);
+    assert_eq!(sq.neighbor_in_dir(n(1, 1), d), Some((n(2, 1), false)));
+}
+
+#[test]
+fn map_in_place() {
+    let mut sq = SquareGraph::<usize, usize, u32>::new(4, 3);
+    sq.map_nodes_in_place(|h, v, n| *n = h * 100 + v);
+    assert_eq!(sq.node_weight((0, 0).into()), Some(&0));
+    assert_eq!(sq.node_weight((3, 1).into()), Some(&301));
+    assert_eq!(sq.node_weight((2, 2).into()), Some(&202));
+
+    sq.map_edges_in_place(|h, v, a, e| *e = h * 100 + v + if a.is_horizontal() { 0 } else { 10 });
+    assert_eq!(
+        sq.edge_weight(((2, 2).into(), Axis::Horizontal).into()),
+        Some(&202)
+    );
+    assert_eq!(
+        sq.edge_weight(((3, 1).into(), Axis::Vertical).into()),
+        Some(&311)
+    );
+    assert_eq!(
+        sq.edge_weight(((3, 1).into(), Axis::Horizontal).into()),
+        None
+    );
+}
+
+#[test]
+fn quadrants() {
+    let sq = SquareGraph::<_, _, u32, HVLoop>::new_with(5, 3, |x, y| (x, y), |x, y, d| (x, y, d));
+    let q = sq.quadrants();
+    let sizes = q
+        .iter()
+        .map(|q| (q.horizontal_node_count(), q.vertical_node_count()))
+        .collect::<Vec<_>>();
+    assert_eq!(sizes, vec![(3, 2), (2, 2), (3, 1), (2, 1)]);
+    // reassemble the nodes
+    let mut nodes = vec![vec![None; 3]; 5];
+    for (q, &(ho, vo)) in q.iter().zip(&[(0, 0), (3, 0), (0, 2), (3, 2)]) {
+        for (i, col) in q.nodes().iter().enumerate() {
+            for (j, n) in col.iter().enumerate() {
+                assert!(nodes[ho + i][vo + j].replace(*n).is_none());
+            }
+        }
+    }
+    for (x, col) in nodes.iter().enumerate() {
+        for (y, n) in col.iter().enumerate() {
+            assert_eq!(*n, Some((x, y)));
+        }
+    }
+    // edges across the quadrants or looping are dropped
+    assert_eq!(
+        q[1].edge_weight((NodeIndex::new(0, 1), Axis::Horizontal).into()),
+        Some(&(3, 1, Axis::Horizontal))
+    );
+    assert_eq!(
+        q[1].edge_weight((NodeIndex::new(1, 1), Axis::Horizontal).into()),
+        None
+    );
+    assert_eq!(
+        q[0].edge_weight((NodeIndex::new(2, 1), Axis::Vertical).into()),
+        None
+    );
+    assert_eq!(q[3].edge_references().count(), 1);
+}
+
+#[test]
+fn empty() {
+    use std::{cell::Cell, rc::Rc};
+    struct Counter(Rc<Cell<usize>>);
+    impl Drop for Counter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+    fn check<S: Shape>() {
+        let sq = SquareGraph::<Counter, (), u32, S>::empty();
+        assert_eq!(sq.node_count(), 0);
+        assert_eq!(sq.horizontal_node_count(), 0);
+        assert_eq!(sq.vertical_node_count(), 0);
+        assert_eq!(sq.node_identifiers().count(), 0);
+        assert_eq!(sq.edge_references().count(), 0);
+        assert!(sq.node_weight((0, 0).into()).is_none());
+        assert!(sq.nodes().iter().all(|v| v.is_empty()));
+        assert!(sq.horizontal().iter().all(|v| v.is_empty()));
+        assert!(sq.vertical().iter().all(|v| v.is_empty()));
+    }
+    check::<DefaultShape>();
+    check::<HorizontalLoop>();
+    check::<VerticalLoop>();
+    check::<HVLoop>();
+
+    let count = Rc::new(Cell::new(0));
+    let sq = SquareGraph::<_, _, u32>::new_with(
+        2,
+        2,
+        |_, _| Counter(count.clone()),
+        |_, _, _| Counter(count.clone()),
+    );
+    drop(sq);
+    assert_eq!(count.get(), 8);
+    drop(SquareGraph::<Counter, Counter, u32>::empty());
+    assert_eq!(count.get(), 8);
+
+    for &(h, v) in &[(3, 0), (0, 3)] {
+        let sq = SquareGraph::<(), (), u32>::new_with(h, v, |_, _| (), |_, _, _| ());
+        assert_eq!(sq.horizontal_node_count(), 0);
+        assert_eq!(sq.vertical_node_count(), 0);
+    }
+}
+
+#[test]
+fn walkable_from() {
+    // a wall at h == 2 except v == 3
+    let wall = |h: usize, v: usize| h == 2 && v != 3;
+    let sq = SquareGraph::<_, _, u32>::walkable_from(5, 4, wall, |a, b| {
+        !wall(a.0, a.1) && !wall(b.0, b.1)
+    });
+    for e in sq.edge_references() {
+        let (s, t) = (e.source(), e.target());
+        let expected = !wall(s.horizontal as usize, s.vertical as usize)
+            && !wall(t.horizontal as usize, t.vertical as usize);
+        assert_eq!(*e.weight(), expected);
+    }
+    let walkable = EdgeFiltered::from_fn(&sq, |e| *e.weight());
+    let (cost, path) = petgraph::algo::astar(
+        &walkable,
+        (0, 0).into(),
+        |n| n == NodeIndex::new(4, 0),
+        |_| 1,
+        |_| 0,
+    )
+    .unwrap();
+    assert_eq!(cost, 10);
+    assert!(path.contains(&NodeIndex::new(2, 3)));
+    assert!(path.iter().all(|n| !sq.node_weight(*n).unwrap()));
+
+    let sq = SquareGraph::<_, _, u32, HVLoop>::walkable_from(
+        3,
+        2,
+        |_, _| (),
+        |a, b| {
+            assert!(a != b);
+            a.0 == b.0 || (a.0 + 1) % 3 == b.0
+        },
+    );
+    assert!(sq.edge_references().all(|e| *e.weight()));
+    assert_eq!(sq.edge_references().count(), 12);
+}
+
+#[test]
+fn max_dimension() {
+    assert_eq!(SquareGraph::<(), (), u8>::max_dimension(), (256, 256));
+    assert_eq!(
+        SquareGraph::<(), (), usize>::max_dimension(),
+        (usize::MAX, usize::MAX)
+    );
+    let sq = SquareGraph::<(), (), u8>::new(256, 2);
+    assert_eq!(sq.horizontal_node_count(), 256);
+}
+
+#[test]
+#[should_panic(expected = "graph dimension 300x1 exceeds the limit 256x256 of the index type")]
+fn max_dimension_overflow() {
+    SquareGraph::<(), (), u8>::new(300, 1);
+}
+
+#[test]
+fn with_neighbor_weights_mut() {
+    let mut sq = SquareGraph::<u32, (), u32>::new(3, 3);
+    sq.with_neighbor_weights_mut(NodeIndex::new(1, 1), |ns| {
+        assert_eq!(ns.len(), 4);
+        for n in ns.iter_mut() {
+            **n += 1;
+        }
+    });
+    assert_eq!(sq.nodes(), [[0, 1, 0], [1, 0, 1], [0, 1, 0]]);
+
+    sq.with_neighbor_weights_mut(NodeIndex::new(0, 0), |ns| {
+        assert_eq!(ns.len(), 2);
+        for n in ns.iter_mut() {
+            **n += 10;
+        }
+    });
+    assert_eq!(sq.nodes(), [[0, 11, 0], [11, 0, 1], [0, 1, 0]]);
+
+    // both the left and right neighbors are the same node in the loop of size 2.
+    let mut sq = SquareGraph::<u32, (), u32, HorizontalLoop>::new(2, 1);
+    sq.with_neighbor_weights_mut(NodeIndex::new(0, 0), |ns| {
+        assert_eq!(ns.len(), 1);
+        *ns[0] += 1;
+    });
+    assert_eq!(sq.nodes(), [[0], [1]]);
+}
+
+#[test]
+fn resize_with() {
+    let fedge =
+        |h: usize, v: usize, a: Axis| h * 10 + v + if a == Axis::Vertical { 100 } else { 0 };
+    let mut sq = SquareGraph::<usize, usize, u32>::new_with(3, 3, |h, v| h * 10 + v, fedge);
+    sq.resize_with(5, 5, |_, _| 1000, |_, _, _| 2000);
+    assert_eq!(sq.horizontal_node_count(), 5);
+    assert_eq!(sq.vertical_node_count(), 5);
+    assert_eq!(sq.horizontal().len(), 4);
+    assert_eq!(sq.vertical()[0].len(), 4);
+    for h in 0..5 {
+        for v in 0..5 {
+            let n = if h < 3 && v < 3 { h * 10 + v } else { 1000 };
+            assert_eq!(sq.nodes()[h][v], n);
+            if h < 4 {
+                let e = if h < 2 && v < 3 {
+                    fedge(h, v, Axis::Horizontal)
+                } else {
+                    2000
+                };
+                assert_eq!(sq.horizontal()[h][v], e);
+            }
+            if v < 4 {
+                let e = if h < 3 && v < 2 {
+                    fedge(h, v, Axis::Vertical)
+                } else {
+                    2000
+                };
+                assert_eq!(sq.vertical()[h][v], e);
+            }
+        }
+    }
+
+    let mut sq = SquareGraph::<usize, usize, u32>::new_with(5, 5, |h, v| h * 10 + v, fedge);
+    sq.resize_with(2, 2, |_, _| unreachable!(), |_, _, _| unreachable!());
+    assert_eq!(sq.nodes(), [[0, 1], [10, 11]]);
+    assert_eq!(sq.horizontal(), [[0, 1]]);
+    assert_eq!(sq.vertical(), [[100], [110]]);
+
+    // the edge wrapping around is kept by the position.
+    let mut sq =
+        SquareGraph::<usize, usize, u32, HorizontalLoop>::new_with(2, 1, |h, v| h * 10 + v, fedge);
+    sq.resize_with(3, 1, |_, _| 1000, |_, _, _| 2000);
+    assert_eq!(sq.nodes(), [[0], [10], [1000]]);
+    assert_eq!(sq.horizontal(), [[0], [10], [2000]]);
+}
+
+#[test]
+fn from_arrays() {
+    let nz = |x| NonZeroUsize::new(x).unwrap();
+    let array = |h, v| FixedVec2D::new(nz(h), v, |h, v| h * 10 + v);
+    let sq = SquareGraph::<usize, usize, u32>::from_arrays(array(3, 2), array(2, 2), array(3, 1))
+        .unwrap();
+    assert_eq!(sq.nodes(), [[0, 1], [10, 11], [20, 21]]);
+    assert_eq!(sq.horizontal(), [[0, 1], [10, 11]]);
+    assert_eq!(sq.vertical(), [[0], [10], [20]]);
+    let sq = SquareGraph::<usize, usize, u32, HVLoop>::from_arrays(
+        array(3, 2),
+        array(3, 2),
+        array(3, 2),
+    )
+    .unwrap();
+    assert_eq!(sq.horizontal()[2], [20, 21]);
+
+    let err = SquareGraph::<usize, usize, u32>::from_arrays(array(3, 2), array(3, 2), array(3, 1))
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ShapeMismatch::Horizontal {
+            expected: (2, 2),
+            found: (3, 2)
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "the size of the horizontal edges is 3x2, but 2x2 is expected"
+    );
+    assert_eq!(
+        SquareGraph::<usize, usize, u32>::from_arrays(array(3, 2), array(2, 1), array(3, 1))
+            .unwrap_err(),
+        ShapeMismatch::Horizontal {
+            expected: (2, 2),
+            found: (2, 1)
+        }
+    );
+    assert_eq!(
+        SquareGraph::<usize, usize, u32>::from_arrays(array(3, 2), array(2, 2), array(2, 1))
+            .unwrap_err(),
+        ShapeMismatch::Vertical {
+            expected: (3, 1),
+            found: (2, 1)
+        }
+    );
+    assert_eq!(
+        SquareGraph::<usize, usize, u32, VerticalLoop>::from_arrays(
+            array(3, 2),
+            array(2, 2),
+            array(3, 1)
+        )
+        .unwrap_err(),
+        ShapeMismatch::Vertical {
+            expected: (3, 2),
+            found: (3, 1)
+        }
+    );
+
+    // the node index of u8 can't represent the horizontal index 256.
+    let err =
+        SquareGraph::<usize, usize, u8>::from_arrays(array(257, 2), array(256, 2), array(257, 1))
+            .unwrap_err();
+    assert_eq!(
+        err,
+        ShapeMismatch::Nodes {
+            max: (256, 256),
+            found: (257, 2)
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "the size of the nodes is 257x2, but the index type supports up to 256x256"
+    );
+    let sq =
+        SquareGraph::<usize, usize, u8>::from_arrays(array(256, 2), array(255, 2), array(256, 1))
+            .unwrap();
+    assert_eq!(sq.node_weight((255, 1).into()), Some(&2551));
+}
+
+#[test]
+fn row_column() {
+    let sq = SquareGraph::<_, (), u32>::new_with(4, 3, |h, v| h * 10 + v, |_, _, _| ());
+    let row = sq.row(1).collect::<Vec<_>>();
+    assert_eq!(row.len(), 4);
+    assert_eq!(
+        row,
+        [
+            ((0, 1).into(), &1),
+            ((1, 1).into(), &11),
+            ((2, 1).into(), &21),
+            ((3, 1).into(), &31)
+        ]
+    );
+    assert_eq!(sq.row(3).count(), 0);
+    let column = sq.column(2).collect::<Vec<_>>();
+    assert_eq!(
+        column,
+        [
+            ((2, 0).into(), &20),
+            ((2, 1).into(), &21),
+            ((2, 2).into(), &22)
+        ]
+    );
+    assert_eq!(sq.column(4).count(), 0);
+}
+
+#[test]
+fn node_edge_count() {
+    let sq = SquareGraph::<(), (), u32>::new(3, 5);
+    assert_eq!(sq.node_count(), 15);
+    assert_eq!(sq.edge_count(), 2 * 5 + 3 * 4);
+    assert_eq!(NodeCount::node_count(&sq), 15);
+    assert_eq!(EdgeCount::edge_count(&sq), 22);
+    assert_eq!(EdgeCount::edge_count(&sq), sq.edge_references().count());
+
+    let sq = SquareGraph::<(), (), u32, HVLoop>::new(3, 5);
+    assert_eq!(sq.node_count(), 15);
+    assert_eq!(sq.edge_count(), 30);
+    assert_eq!(EdgeCount::edge_count(&sq), sq.edge_references().count());
+
+    assert_eq!(SquareGraph::<(), (), u32>::empty().edge_count(), 0);
+}
+
+#[test]
+fn subgrid() {
+    let sq = SquareGraph::<_, _, u32>::new_with(5, 5, |h, v| h * 10 + v, |h, v, a| (h, v, a));
+    let sub = sq.subgrid(NodeIndex::new(1, 2), 2, 2).unwrap();
+    assert_eq!(sub.origin(), (1, 2));
+    let nodes = sub.node_identifiers().collect::<Vec<_>>();
+    assert_eq!(nodes, [(1, 2), (1, 3), (2, 2), (2, 3)]);
+    for n in nodes.iter() {
+        let neighbors = sub.neighbors(*n).collect::<std::collections::HashSet<_>>();
+        assert_eq!(neighbors.len(), 2, "{:?}", n);
+        assert!(neighbors.iter().all(|x| sub.contains(*x)));
+    }
+    assert_eq!(
+        sub.neighbors(NodeIndex::new(1, 2)).collect::<Vec<_>>(),
+        [(2, 2), (1, 3)]
+    );
+    // the node outside of the region has no neighbors even if it is adjacent to the region.
+    assert_eq!(sub.neighbors(NodeIndex::new(0, 2)).count(), 0);
+    assert_eq!(sub.node_weight(NodeIndex::new(2, 3)), Some(&23));
+    assert_eq!(sub.node_weight(NodeIndex::new(3, 3)), None);
+    let e = EdgeIndex::from((NodeIndex::new(1, 2), Axis::Horizontal));
+    assert_eq!(sub.edge_weight(e), Some(&(1, 2, Axis::Horizontal)));
+    let e = EdgeIndex::from((NodeIndex::new(2, 2), Axis::Horizontal));
+    assert_eq!(sub.edge_weight(e), None);
+    let e = EdgeIndex::from((NodeIndex::new(1, 1), Axis::Vertical));
+    assert_eq!(sub.edge_weight(e), None);
+
+    // bfs doesn't go out of the region.
+    let mut bfs = Bfs::new(&sub, NodeIndex::new(1, 2));
+    let mut count = 0;
+    while let Some(n) = bfs.next(&sub) {
+        assert!(sub.contains(n));
+        count += 1;
+    }
+    assert_eq!(count, 4);
+
+    // the loop edges are clipped unless the region covers the whole axis.
+    let sq = SquareGraph::<(), (), u32, HorizontalLoop>::new(5, 5);
+    let sub = sq.subgrid(NodeIndex::new(0, 0), 2, 2).unwrap();
+    assert_eq!(sub.neighbors(NodeIndex::new(0, 0)).count(), 2);
+    let sub = sq.subgrid(NodeIndex::new(0, 0), 5, 1).unwrap();
+    assert_eq!(sub.neighbors(NodeIndex::new(0, 0)).count(), 2);
+    let e = EdgeIndex::from((NodeIndex::new(4, 0), Axis::Horizontal));
+    assert_eq!(sub.edge_weight(e), Some(&()));
+
+    assert!(sq.subgrid(NodeIndex::new(4, 4), 2, 1).is_none());
+    assert_eq!(
+        sq.subgrid(NodeIndex::new(5, 5), 0, 0)
+            .unwrap()
+            .node_identifiers()
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn astar_square() {
+    use crate::algo::astar_square;
+    let sq = SquareGraph::<_, _, u32>::new_with(
+        3,
+        4,
+        |_, _| (),
+        |x, y, d| (1 + x + 2 * y) as i32 * (if d.is_horizontal() { 1 } else { 3 }),
+    );
+
+    let (d, p) = astar_square(&sq, (0, 0).into(), (2, 1).into(), |e| *e.weight()).unwrap();
+    assert_eq!(d, 10);
+    assert_eq!(p, [(0, 0), (0, 1), (1, 1), (2, 1)]);
+
+    let (d, p) = astar_square(&sq, (2, 1).into(), (0, 0).into(), |e| *e.weight()).unwrap();
+    assert_eq!(d, 10);
+    assert_eq!(p, [(2, 1), (1, 1), (0, 1), (0, 0)]);
+
+    assert_eq!(
+        astar_square(&sq, (0, 0).into(), (3, 0).into(), |e| *e.weight()),
+        None
+    );
+
+    // the path across the border is the shortest.
+    let sq = SquareGraph::<_, _, u32, HVLoop>::new_with(
+        8,
+        8,
+        |_, _| (),
+        |x, _, d| if d.is_horizontal() && x == 3 { 2 } else { 1 },
+    );
+    for &(from, to) in &[((1, 0), (6, 0)), ((0, 1), (7, 6)), ((4, 4), (1, 1))] {
+        let expected = petgraph::algo::dijkstra(&sq, from.into(), Some(to.into()), |e| *e.weight());
+        let (d, p) = astar_square(&sq, from.into(), to.into(), |e| *e.weight()).unwrap();
+        assert_eq!(d, expected[&to.into()]);
+        assert_eq!(p.first(), Some(&from.into()));
+        assert_eq!(p.last(), Some(&to.into()));
+    }
+}
+
+#[test]
+fn new_with_zero_size() {
+    fn check<S: Shape>() {
+        for (h, v) in IntoIterator::into_iter([(3, 0), (0, 3), (0, 0)]) {
+            let sq = SquareGraph::<(), (), u32, S>::new_with(
+                h,
+                v,
+                |_, _| unreachable!(),
+                |_, _, _| unreachable!(),
+            );
+            assert_eq!(sq.node_count(), 0);
+            assert_eq!(sq.edge_count(), 0);
+            assert_eq!(sq.node_identifiers().count(), 0);
+        }
+    }
+    check::<DefaultShape>();
+    check::<HorizontalLoop>();
+    check::<VerticalLoop>();
+    check::<HVLoop>();
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_node_references() {
+    use rayon::prelude::*;
+    let sq = SquareGraph::<_, (), u32>::new_with(50, 40, |h, v| h * 100 + v, |_, _, _| ());
+    let par = sq.par_node_references().collect::<Vec<_>>();
+    assert!(par.iter().copied().eq(sq.node_references()));
+    for (i, n) in par {
+        assert_eq!(*n, i.horizontal.index() * 100 + i.vertical.index());
+    }
+}
+
+#[test]
+fn border_nodes() {
+    let sq = SquareGraph::<(), (), u32>::new(5, 5);
+    assert_eq!(sq.border_nodes().count(), 16);
+    assert!(sq.is_border(NodeIndex::new(0, 2)));
+    assert!(!sq.is_border(NodeIndex::new(2, 2)));
+    assert!(!sq.is_border(NodeIndex::new(5, 2)));
+    assert!(sq.border_nodes().all(|n| {
+        let (h, v) = (n.horizontal, n.vertical);
+        h == 0 || h == 4 || v == 0 || v == 4
+    }));
+    let sq = SquareGraph::<(), (), u32, HVLoop>::new(5, 5);
+    assert_eq!(sq.border_nodes().count(), 0);
+    assert!(!sq.is_border(NodeIndex::new(0, 0)));
+    let sq = SquareGraph::<(), (), u32, HorizontalLoop>::new(5, 5);
+    assert_eq!(sq.border_nodes().count(), 10);
+    assert!(sq
+        .border_nodes()
+        .all(|n| n.vertical == 0 || n.vertical == 4));
+    assert!(!sq.is_border(NodeIndex::new(0, 2)));
+    let sq = SquareGraph::<(), (), u32, VerticalLoop>::new(5, 3);
+    assert_eq!(sq.border_nodes().count(), 6);
+    assert!(sq
+        .border_nodes()
+        .all(|n| n.horizontal == 0 || n.horizontal == 4));
+}
+
+#[test]
+fn to_petgraph() {
+    let sq = SquareGraph::<_, _, u32>::new_with(
+        4,
+        3,
+        |h, v| (h, v),
+        |h, v, a| (h, v, a == Axis::Horizontal),
+    );
+    let (g, index) = sq.to_petgraph();
+    assert_eq!(g.node_count(), sq.node_count());
+    assert_eq!(g.edge_count(), sq.edge_count());
+    for (n, w) in sq.node_references() {
+        assert_eq!(g.node_weight(index(n)), Some(w));
+    }
+    let e = g
+        .find_edge(index(NodeIndex::new(1, 2)), index(NodeIndex::new(2, 2)))
+        .unwrap();
+    assert_eq!(g[e], (1, 2, true));
+    let e = g
+        .find_edge(index(NodeIndex::new(3, 1)), index(NodeIndex::new(3, 0)))
+        .unwrap();
+    assert_eq!(g[e], (3, 0, false));
+    assert!(g
+        .find_edge(index(NodeIndex::new(0, 0)), index(NodeIndex::new(3, 0)))
+        .is_none());
+
+    let sq = SquareGraph::<(), (), u32, HVLoop>::new(4, 3);
+    let (g, index) = sq.to_petgraph();
+    assert_eq!(g.edge_count(), 24);
+    assert!(g
+        .find_edge(index(NodeIndex::new(0, 0)), index(NodeIndex::new(3, 0)))
+        .is_some());
+}
+
+#[test]
+fn horizontal_vertical_edges() {
+    let fedge = |h: usize, v: usize, a: Axis| (h, v, a);
+    let sq = SquareGraph::<(), _, u32>::new_with(4, 3, |_, _| (), fedge);
+    assert_eq!(sq.horizontal_edges().count(), 9);
+    assert_eq!(sq.vertical_edges().count(), 8);
+    assert!(sq
+        .horizontal_edges()
+        .all(|(n, e)| *e == (n.horizontal.index(), n.vertical.index(), Axis::Horizontal)));
+    assert!(sq
+        .vertical_edges()
+        .all(|(n, e)| *e == (n.horizontal.index(), n.vertical.index(), Axis::Vertical)));
+    assert_eq!(
+        sq.horizontal_edges().nth(4),
+        Some((NodeIndex::new(1, 1), &(1, 1, Axis::Horizontal)))
+    );
+    assert_eq!(
+        sq.vertical_edges().last(),
+        Some((NodeIndex::new(3, 1), &(3, 1, Axis::Vertical)))
+    );
+    let count = sq.horizontal_edges().count() + sq.vertical_edges().count();
+    assert_eq!(count, sq.edge_count());
+
+    let sq = SquareGraph::<(), _, u32, HorizontalLoop>::new_with(4, 3, |_, _| (), fedge);
+    assert_eq!(sq.horizontal_edges().count(), 12);
+    assert_eq!(sq.vertical_edges().count(), 8);
+    assert_eq!(
+        sq.horizontal_edges().last(),
+        Some((NodeIndex::new(3, 2), &(3, 2, Axis::Horizontal)))
+    );
+    let sq = SquareGraph::<(), _, u32, HVLoop>::new_with(4, 3, |_, _| (), fedge);
+    assert_eq!(sq.vertical_edges().count(), 12);
+    assert_eq!(
+        sq.vertical_edges().last(),
+        Some((NodeIndex::new(3, 2), &(3, 2, Axis::Vertical)))
+    );
+}
+
+#[test]
+fn memory_usage() {
+    let sq = SquareGraph::<f32, i32, u32>::new_with(10, 10, |_, _| 0., |_, _, _| 0);
+    // the values and the heads of the nodes, the horizontal and the vertical edges.
+    let head = std::mem::size_of::<&[f32]>();
+    let nodes = 10 * 10 * 4 + 10 * head;
+    let horizontal = 9 * 10 * 4 + 9 * head;
+    let vertical = 10 * 9 * 4 + 10 * head;
+    assert_eq!(sq.memory_usage(), nodes + horizontal + vertical);
+}